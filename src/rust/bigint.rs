@@ -0,0 +1,86 @@
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use crate::expression::{Argument, GlobalState};
+use crate::keywords::nth_argument;
+
+pub fn as_bigint(arg: &Argument) -> Option<BigInt> {
+    match arg {
+        Argument::Integer(i) => Some(BigInt::from(*i)),
+        Argument::BigInt(i) => Some(i.clone()),
+        _ => None,
+    }
+}
+
+// results that fit into an i64 become ordinary integers again
+pub fn normalize(integer: BigInt) -> Argument {
+    integer.to_i64().map_or(Argument::BigInt(integer), Argument::Integer)
+}
+
+pub fn to_f64(integer: &BigInt) -> f64 {
+    integer.to_f64().unwrap_or(f64::NAN)
+}
+
+// only exact quotients, like i64 division in keywords::divide
+pub fn divide(a: &BigInt, b: &BigInt) -> Option<BigInt> {
+    if b.is_zero() || !(a % b).is_zero() {
+        None
+    } else {
+        Some(a / b)
+    }
+}
+
+pub fn modulo(a: &BigInt, b: &BigInt) -> Option<BigInt> {
+    if b.is_zero() {
+        None
+    } else {
+        Some(a % b)
+    }
+}
+
+// results of ** with more bits than this are rejected, computing them could take
+// minutes or all of the memory of the program embedding kfkscript
+const MAX_POWER_BITS: u64 = 1 << 20;
+
+// a^b has at least (bits(a) - 1) * b bits, this only has to be checked with the
+// bigint feature as integer powers become floats without it
+pub fn check_power(args: &[Argument], line_number: u32) -> Result<()> {
+    if let (Some(base), Some(exponent)) = (
+        args.first().and_then(as_bigint),
+        args.get(1).and_then(as_bigint),
+    ) {
+        // 0, 1 and -1 stay that small whatever the exponent
+        let minimum_bits = match base.bits().saturating_sub(1) {
+            0 => 0,
+            bits => exponent.to_u64().map_or(u64::MAX, |exponent| bits.saturating_mul(exponent)),
+        };
+        if exponent.is_positive() && minimum_bits > MAX_POWER_BITS {
+            Err(eyre!(format!(
+                "result of ** {base} {exponent} too large, it would have more than {MAX_POWER_BITS} bits in line {line_number}"
+            )))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn power(a: &BigInt, b: &BigInt) -> Option<BigInt> {
+    b.to_u32().map(|b| a.pow(b))
+}
+
+// turns an integer into an arbitrary-precision one, arithmetic on it then never
+// overflows into a float
+#[allow(clippy::needless_pass_by_value)]
+pub fn bigint(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let arg = nth_argument(&args, 0, "bigint", new_state.line_number)?;
+    new_state.ret = Argument::BigInt(as_bigint(arg).ok_or_else(|| {
+        eyre!(format!(
+            "cannot use argument of type {} with keyword bigint in line {}",
+            arg.type_name(),
+            new_state.line_number
+        ))
+    })?);
+    Ok(new_state)
+}
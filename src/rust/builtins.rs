@@ -0,0 +1,261 @@
+use color_eyre::eyre::{eyre, Result};
+
+#[cfg(feature = "bigint")]
+use crate::bigint;
+use crate::expression::{Argument, GlobalState, Implementation, KeywordImplementation, NativeClosure};
+use crate::{keywords, lists, maps, math, strings};
+
+pub fn insert_keyword(
+    global_state: GlobalState,
+    keyword_impl: KeywordImplementation,
+) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    if new_state.keywords.contains_key(&keyword_impl.name) {
+        Err(eyre!(format!(
+            "Keyword {} already registered. Overwriting keyword registrations is not allowed.",
+            keyword_impl.name
+        )))
+    } else {
+        new_state
+            .keywords
+            .insert(keyword_impl.name.clone(), keyword_impl);
+        Ok(new_state)
+    }
+}
+
+pub fn register_keyword(
+    global_state: GlobalState,
+    name: &str,
+    implementation: fn(GlobalState, Vec<Argument>) -> Result<GlobalState>,
+    number_of_arguments: u32,
+) -> Result<GlobalState> {
+    insert_keyword(
+        global_state,
+        KeywordImplementation {
+            name: name.into(),
+            implementation: Implementation::Native(implementation),
+            number_of_arguments,
+            variadic: false,
+            short_circuit: None,
+        },
+    )
+}
+
+pub fn register_closure_keyword(
+    global_state: GlobalState,
+    name: &str,
+    implementation: NativeClosure,
+    number_of_arguments: u32,
+) -> Result<GlobalState> {
+    insert_keyword(
+        global_state,
+        KeywordImplementation {
+            name: name.into(),
+            implementation: Implementation::Closure(implementation),
+            number_of_arguments,
+            variadic: false,
+            short_circuit: None,
+        },
+    )
+}
+
+// variadic keywords take number_of_arguments arguments unless called as name@N
+pub fn register_variadic_keyword(
+    global_state: GlobalState,
+    name: &str,
+    implementation: fn(GlobalState, Vec<Argument>) -> Result<GlobalState>,
+    number_of_arguments: u32,
+) -> Result<GlobalState> {
+    insert_keyword(
+        global_state,
+        KeywordImplementation {
+            name: name.into(),
+            implementation: Implementation::Native(implementation),
+            number_of_arguments,
+            variadic: true,
+            short_circuit: None,
+        },
+    )
+}
+
+// arguments following the first one for which short_circuit returns true are
+// skipped instead of evaluated. Short circuiting keywords are always variadic
+pub fn register_short_circuit_keyword(
+    global_state: GlobalState,
+    name: &str,
+    implementation: fn(GlobalState, Vec<Argument>) -> Result<GlobalState>,
+    number_of_arguments: u32,
+    short_circuit: fn(&Argument) -> bool,
+) -> Result<GlobalState> {
+    insert_keyword(
+        global_state,
+        KeywordImplementation {
+            name: name.into(),
+            implementation: Implementation::Native(implementation),
+            number_of_arguments,
+            variadic: true,
+            short_circuit: Some(short_circuit),
+        },
+    )
+}
+
+fn register_core_keywords(global_state: GlobalState) -> Result<GlobalState> {
+    let mut global_state = global_state;
+    global_state = register_variadic_keyword(global_state, "println", keywords::println, 1)?;
+    global_state = register_keyword(global_state, "readln", keywords::readln, 0)?;
+    global_state = register_variadic_keyword(global_state, "+", keywords::add, 2)?;
+    global_state = register_variadic_keyword(global_state, "-", keywords::subtract, 2)?;
+    global_state = register_variadic_keyword(global_state, "*", keywords::multiply, 2)?;
+    global_state = register_variadic_keyword(global_state, "/", keywords::divide, 2)?;
+    global_state = register_keyword(global_state, "%", keywords::modulo, 2)?;
+    global_state = register_keyword(global_state, "**", keywords::power, 2)?;
+    global_state = register_keyword(global_state, "if", keywords::if_, 1)?;
+    global_state = register_keyword(global_state, "while", keywords::while_, 1)?;
+    global_state = register_keyword(global_state, "for", keywords::for_, 2)?;
+    global_state = register_keyword(global_state, "break", keywords::break_, 0)?;
+    global_state = register_keyword(global_state, "exit", keywords::exit, 1)?;
+    global_state = register_keyword(global_state, "continue", keywords::continue_, 0)?;
+    global_state = register_keyword(global_state, "else", keywords::else_, 0)?;
+    global_state = register_keyword(global_state, "end", keywords::end, 0)?;
+    global_state = register_keyword(global_state, "let", keywords::let_, 2)?;
+    global_state = register_keyword(global_state, "tel", keywords::tel, 1)?;
+    global_state = register_keyword(global_state, "==", keywords::eq, 2)?;
+    global_state = register_keyword(global_state, "!=", keywords::not_eq, 2)?;
+    global_state = register_keyword(global_state, "<", keywords::less_than, 2)?;
+    global_state = register_keyword(global_state, ">", keywords::greater_than, 2)?;
+    global_state = register_keyword(global_state, "<=", keywords::less_or_eq, 2)?;
+    global_state = register_keyword(global_state, ">=", keywords::greater_or_eq, 2)?;
+    global_state = register_keyword(global_state, "!", keywords::not, 1)?;
+    global_state = register_short_circuit_keyword(
+        global_state,
+        "and",
+        keywords::and,
+        2,
+        keywords::and_short_circuits,
+    )?;
+    global_state = register_short_circuit_keyword(
+        global_state,
+        "or",
+        keywords::or,
+        2,
+        keywords::or_short_circuits,
+    )?;
+    global_state = register_keyword(global_state, "true", keywords::true_, 0)?;
+    global_state = register_keyword(global_state, "false", keywords::false_, 0)?;
+    global_state = register_keyword(global_state, "nil", keywords::nil, 0)?;
+    global_state = register_keyword(global_state, "scope::push", keywords::scope_push, 0)?;
+    global_state = register_keyword(global_state, "scope::pop", keywords::scope_pop, 0)?;
+    global_state = register_keyword(
+        global_state,
+        "scope::outer::let",
+        keywords::scope_outer_let,
+        2,
+    )?;
+    global_state = register_keyword(
+        global_state,
+        "scope::outer::tel",
+        keywords::scope_outer_tel,
+        1,
+    )?;
+    global_state = register_keyword(global_state, "return", keywords::return_, 1)?;
+    global_state = register_keyword(global_state, "subroutine", keywords::subroutine, 1)?;
+    global_state = register_keyword(global_state, "run", keywords::run, 1)?;
+    global_state = register_keyword(global_state, "keyword", keywords::keyword, 2)?;
+    Ok(global_state)
+}
+
+fn register_math_keywords(global_state: GlobalState) -> Result<GlobalState> {
+    let mut global_state = global_state;
+    global_state = register_keyword(global_state, "math::abs", math::abs, 1)?;
+    global_state = register_keyword(global_state, "math::floor", math::floor, 1)?;
+    global_state = register_keyword(global_state, "math::ceil", math::ceil, 1)?;
+    global_state = register_keyword(global_state, "math::round", math::round, 1)?;
+    global_state = register_keyword(global_state, "math::sqrt", math::sqrt, 1)?;
+    global_state = register_variadic_keyword(global_state, "math::min", math::min, 2)?;
+    global_state = register_variadic_keyword(global_state, "math::max", math::max, 2)?;
+    global_state = register_keyword(global_state, "math::sin", math::sin, 1)?;
+    global_state = register_keyword(global_state, "math::cos", math::cos, 1)?;
+    global_state = register_keyword(global_state, "math::tan", math::tan, 1)?;
+    global_state = register_keyword(global_state, "math::log", math::log, 1)?;
+    global_state = register_keyword(global_state, "math::exp", math::exp, 1)?;
+    global_state = register_keyword(global_state, "math::pi", math::pi, 0)?;
+    #[cfg(feature = "bigint")]
+    {
+        global_state = register_keyword(global_state, "bigint", bigint::bigint, 1)?;
+    }
+    Ok(global_state)
+}
+
+fn register_integer_keywords(global_state: GlobalState) -> Result<GlobalState> {
+    let mut global_state = global_state;
+    global_state = register_keyword(global_state, "bit::and", keywords::bit_and, 2)?;
+    global_state = register_keyword(global_state, "bit::or", keywords::bit_or, 2)?;
+    global_state = register_keyword(global_state, "bit::xor", keywords::bit_xor, 2)?;
+    global_state = register_keyword(global_state, "bit::not", keywords::bit_not, 1)?;
+    global_state = register_keyword(global_state, "bit::shl", keywords::bit_shl, 2)?;
+    global_state = register_keyword(global_state, "bit::shr", keywords::bit_shr, 2)?;
+    global_state = register_variadic_keyword(global_state, "int::div", keywords::integer_divide, 2)?;
+    global_state = register_keyword(global_state, "int::gcd", keywords::gcd, 2)?;
+    global_state = register_keyword(global_state, "int::lcm", keywords::lcm, 2)?;
+    global_state = register_keyword(global_state, "int::checked-add", keywords::checked_add, 2)?;
+    global_state = register_keyword(global_state, "int::checked-sub", keywords::checked_subtract, 2)?;
+    global_state = register_keyword(global_state, "int::checked-mul", keywords::checked_multiply, 2)?;
+    global_state = register_keyword(global_state, "int::checked-pow", keywords::checked_power, 2)?;
+    Ok(global_state)
+}
+
+fn register_list_keywords(global_state: GlobalState) -> Result<GlobalState> {
+    let mut global_state = global_state;
+    global_state = register_variadic_keyword(global_state, "list::new", lists::new, 0)?;
+    global_state = register_keyword(global_state, "list::push", lists::push, 2)?;
+    global_state = register_keyword(global_state, "list::pop", lists::pop, 1)?;
+    global_state = register_keyword(global_state, "list::get", lists::get, 2)?;
+    global_state = register_keyword(global_state, "list::set", lists::set, 3)?;
+    global_state = register_keyword(global_state, "list::len", lists::len, 1)?;
+    global_state = register_keyword(global_state, "list::slice", lists::slice, 3)?;
+    Ok(global_state)
+}
+
+fn register_map_keywords(global_state: GlobalState) -> Result<GlobalState> {
+    let mut global_state = global_state;
+    global_state = register_variadic_keyword(global_state, "map::new", maps::new, 0)?;
+    global_state = register_keyword(global_state, "map::get", maps::get, 2)?;
+    global_state = register_keyword(global_state, "map::set", maps::set, 3)?;
+    global_state = register_keyword(global_state, "map::has", maps::has, 2)?;
+    global_state = register_keyword(global_state, "map::remove", maps::remove, 2)?;
+    global_state = register_keyword(global_state, "map::keys", maps::keys, 1)?;
+    global_state = register_keyword(global_state, "map::values", maps::values, 1)?;
+    global_state = register_keyword(global_state, "map::len", maps::len, 1)?;
+    Ok(global_state)
+}
+
+fn register_string_keywords(global_state: GlobalState) -> Result<GlobalState> {
+    let mut global_state = global_state;
+    global_state = register_keyword(global_state, "str::cast", strings::cast, 1)?;
+    global_state = register_keyword(global_state, "str::len", strings::len, 1)?;
+    global_state = register_variadic_keyword(global_state, "str::concat", strings::concat, 2)?;
+    global_state = register_keyword(global_state, "str::slice", strings::slice, 3)?;
+    global_state = register_keyword(global_state, "str::index-of", strings::index_of, 2)?;
+    global_state = register_keyword(global_state, "str::split", strings::split, 2)?;
+    global_state = register_keyword(global_state, "str::join", strings::join, 2)?;
+    global_state = register_keyword(global_state, "str::trim", strings::trim, 1)?;
+    global_state = register_keyword(global_state, "str::upper", strings::upper, 1)?;
+    global_state = register_keyword(global_state, "str::lower", strings::lower, 1)?;
+    global_state = register_keyword(global_state, "str::replace", strings::replace, 3)?;
+    global_state = register_keyword(global_state, "str::starts-with", strings::starts_with, 2)?;
+    global_state = register_keyword(global_state, "str::ends-with", strings::ends_with, 2)?;
+    global_state = register_keyword(global_state, "str::repeat", strings::repeat, 2)?;
+    Ok(global_state)
+}
+
+// every keyword that comes with kfkscript
+pub fn register_builtin_keywords(global_state: GlobalState) -> Result<GlobalState> {
+    let mut global_state = global_state;
+    global_state = register_core_keywords(global_state)?;
+    global_state = register_math_keywords(global_state)?;
+    global_state = register_integer_keywords(global_state)?;
+    global_state = register_list_keywords(global_state)?;
+    global_state = register_map_keywords(global_state)?;
+    global_state = register_string_keywords(global_state)?;
+    Ok(global_state)
+}
@@ -1,12 +1,14 @@
 use std::{iter::Peekable, slice::Iter};
 
 use crate::{
-    expression::{self, Argument, GlobalState, NestingState},
-    token::{self, Token},
+    expression::{self, Argument, GlobalState, Jump, NestingState},
+    token::Token,
 };
 
-use color_eyre::eyre::{OptionExt, Result};
+use color_eyre::eyre::{eyre, OptionExt, Result};
 
+// blocks don't have a value, so if, else, while, for and end all leave nil behind
+// instead of whatever their condition evaluated to
 #[allow(clippy::needless_pass_by_value)]
 pub fn if_(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
     let arg = args.first().ok_or_eyre(format!(
@@ -14,10 +16,8 @@ pub fn if_(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState
         global_state.line_number
     ))?;
     let mut new_state = global_state;
-    if match arg {
-        Argument::Number(n) => (n - 1.0).abs() < 10e-9,
-        Argument::KfkString(s) => !s.is_empty(),
-    } {
+    new_state.ret = Argument::Nil;
+    if arg.is_truthy() {
         new_state.nesting.push(expression::NestingState::If);
     } else {
         new_state.nesting.push(expression::NestingState::Else);
@@ -25,8 +25,52 @@ pub fn if_(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState
     Ok(new_state)
 }
 
+// a while whose condition holds asks main_loop to remember where it started so
+// the matching end can jump back and have the condition evaluated again
+#[allow(clippy::needless_pass_by_value)]
+pub fn while_(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let arg = args.first().ok_or_eyre(format!(
+        "Expected argument to keyword while in line {}",
+        global_state.line_number
+    ))?;
+    let mut new_state = global_state;
+    new_state.ret = Argument::Nil;
+    if arg.is_truthy() {
+        new_state.nesting.push(NestingState::While);
+        new_state.jump = Some(Jump::LoopStart);
+    } else {
+        new_state.nesting.push(NestingState::Ignore);
+    }
+    Ok(new_state)
+}
+
+// binds the first item of the list to the variable and asks main_loop to remember
+// where the body starts, the matching end binds the next item and jumps back there
+#[allow(clippy::needless_pass_by_value)]
+pub fn for_(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let variable = args.first().ok_or_eyre(format!("Name of variable in for not found in line {}. This error should never surface, please inform the developers of Kfkscript.", global_state.line_number))?.clone();
+    let mut items = match args.get(1).ok_or_eyre(format!("List in for not found in line {}. This error should never surface, please inform the developers of Kfkscript.", global_state.line_number))? {
+        Argument::List(items) => items.clone().into_iter(),
+        arg => Err(eyre!(format!("cannot iterate over argument of type {} with keyword for in line {}", arg.type_name(), global_state.line_number)))?,
+    };
+    let mut new_state = global_state;
+    new_state.ret = Argument::Nil;
+    if let Some(item) = items.next() {
+        new_state.variables.insert(variable.clone(), item);
+        new_state.nesting.push(NestingState::For {
+            variable,
+            remaining: items.collect(),
+        });
+        new_state.jump = Some(Jump::LoopBodyStart);
+    } else {
+        new_state.nesting.push(NestingState::Ignore);
+    }
+    Ok(new_state)
+}
+
 pub fn else_(global_state: GlobalState, _: Vec<Argument>) -> Result<GlobalState> {
     let mut new_state = global_state;
+    new_state.ret = Argument::Nil;
     let previous_nesting = new_state.nesting.last().ok_or_eyre(format!(
         "Cannot use else when there is no previous if in line {}",
         new_state.line_number
@@ -37,73 +81,119 @@ pub fn else_(global_state: GlobalState, _: Vec<Argument>) -> Result<GlobalState>
     } else if previous_nesting == &NestingState::Else {
         new_state.nesting.pop();
         new_state.nesting.push(NestingState::If);
+    } else if matches!(
+        previous_nesting,
+        NestingState::While | NestingState::For { .. } | NestingState::Continue(_)
+    ) {
+        Err(eyre!(format!(
+            "Cannot use else in a loop in line {}",
+            new_state.line_number
+        )))?;
     }
     Ok(new_state)
 }
 
 pub fn end(global_state: GlobalState, _: Vec<Argument>) -> Result<GlobalState> {
     let mut new_state = global_state;
-    // println!("END, returning {:?}", new_state.ret);
-    let previous_state = new_state.nesting.pop().ok_or_eyre(format!("Cannot use end when there is no previous if, subroutine or keyword registration in line {}", new_state.line_number))?;
-    if previous_state == NestingState::SubroutineDefinition {
-        todo!();
+    new_state.ret = Argument::Nil;
+    let previous_state = match new_state.nesting.pop().ok_or_eyre(format!("Cannot use end when there is no previous if, while, for, subroutine or keyword registration in line {}", new_state.line_number))? {
+        NestingState::Continue(loop_state) => *loop_state,
+        previous_state => previous_state,
+    };
+    match previous_state {
+        NestingState::SubroutineDefinition | NestingState::KeywordDefinition => {
+            Err(eyre!(format!("Definition ended by keyword end instead of by the interpreter in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number)))?;
+        }
+        NestingState::While => new_state.jump = Some(Jump::LoopEnd),
+        NestingState::For {
+            variable,
+            remaining,
+        } => {
+            let mut items = remaining.into_iter();
+            if let Some(item) = items.next() {
+                new_state.variables.insert(variable.clone(), item);
+                new_state.nesting.push(NestingState::For {
+                    variable,
+                    remaining: items.collect(),
+                });
+                new_state.jump = Some(Jump::LoopRepeat);
+            } else {
+                new_state.jump = Some(Jump::Break);
+            }
+        }
+        NestingState::If | NestingState::Else | NestingState::Ignore | NestingState::Continue(_) => {}
     }
     Ok(new_state)
 }
 
-// if we want to ignore the next invocation (NestingState::Else or NestingSatet::Ignore)
-// we will skip forward as many tokens as the next keyword implies unless of course
-// we have another control flow keyword (end or else). That will get executed
-//
-// nested ifs that we want to ignore change this game slightly: they use the NestingState::Ignore
-// meaning the else keyword becomes a no-op. We still need the NestingState::Ignore in our nesting stack
-// because we need to balance our ifs and ends. The end keyword will treat a NestingState::If and
-// NestingState::Ignore the same: pop them
-pub fn hurenmüll(
-    tokens: &mut Peekable<Iter<Token>>,
-    global_state: GlobalState,
-) -> Result<(u32, GlobalState)> {
-    let mut next_token = tokens.next().unwrap();
-    while match next_token {
-        Token::Keyword(k) => k.lexem != "end",
-        Token::KfkString(_) => true,
-        Token::Number(_) => true,
-    } {
-        next_token = tokens.peek().unwrap();
-        if match next_token {
-            Token::Keyword(k) => k.lexem != "end",
-            Token::KfkString(_) => true,
-            Token::Number(_) => true,
-        } {
-            tokens.next();
-        }
+fn innermost_loop(global_state: &GlobalState, keyword: &str) -> Result<usize> {
+    global_state
+        .nesting
+        .iter()
+        .rposition(|nesting| matches!(nesting, NestingState::While | NestingState::For { .. }))
+        .ok_or_eyre(format!(
+            "Cannot use {keyword} outside of a loop in line {}",
+            global_state.line_number
+        ))
+}
+
+// break turns the innermost loop and every block opened inside of it into
+// NestingState::Ignore, so main_loop skips forward to the end of the loop while
+// keeping ifs and ends balanced
+pub fn break_(global_state: GlobalState, _: Vec<Argument>) -> Result<GlobalState> {
+    let loop_index = innermost_loop(&global_state, "break")?;
+    let mut new_state = global_state;
+    for nesting in &mut new_state.nesting[loop_index..] {
+        *nesting = NestingState::Ignore;
+    }
+    new_state.jump = Some(Jump::Break);
+    Ok(new_state)
+}
+
+// continue does the same as break for the blocks opened inside the loop, the loop
+// itself gets wrapped in NestingState::Continue so its end still jumps back
+pub fn continue_(global_state: GlobalState, _: Vec<Argument>) -> Result<GlobalState> {
+    let loop_index = innermost_loop(&global_state, "continue")?;
+    let mut new_state = global_state;
+    let loop_state = std::mem::replace(&mut new_state.nesting[loop_index], NestingState::Ignore);
+    new_state.nesting[loop_index] = NestingState::Continue(Box::new(loop_state));
+    for nesting in &mut new_state.nesting[loop_index + 1..] {
+        *nesting = NestingState::Ignore;
     }
-    Ok((0, global_state))
+    Ok(new_state)
+}
+
+// keywords that open a block which has to be closed by a matching end
+#[must_use]
+pub fn is_block_start(lexem: &str) -> bool {
+    matches!(lexem, "if" | "while" | "for" | "subroutine" | "keyword")
 }
 
+// if we want to ignore a branch (NestingState::Else, NestingState::Ignore or
+// NestingState::Continue) we skip
+// forward until we find the else or end that belongs to it. Those are not skipped but
+// left for main_loop to execute like any other keyword. Blocks nested inside the
+// skipped branch are skipped as a whole, including their own else and end
 pub fn skip_tokens(
     token_iter: &mut Peekable<Iter<Token>>,
     global_state: GlobalState,
 ) -> Result<GlobalState> {
-    let mut new_state = global_state;
-    let mut nesting_counter = 1;
-    while nesting_counter > 0
-    {
-        let next_token = token_iter
-            .next()
-            .ok_or_eyre(format!(
-                "no end found to terminate subroutine definition in line {}",
-                new_state.line_number
-            ))?;
+    let mut nesting_counter = 0;
+    while let Some(next_token) = token_iter.peek() {
         if let Token::Keyword(keyword) = next_token {
-            if keyword.lexem == "if" {
-                new_state.nesting.push(NestingState::Ignore);
+            if nesting_counter == 0 && (keyword.lexem == "else" || keyword.lexem == "end") {
+                return Ok(global_state);
+            }
+            if is_block_start(&keyword.lexem) {
                 nesting_counter += 1;
             } else if keyword.lexem == "end" {
-                new_state.nesting.pop();
                 nesting_counter -= 1;
             }
         }
+        token_iter.next();
     }
-    Ok(new_state)
+    Err(eyre!(format!(
+        "no end found to terminate block in line {}",
+        global_state.line_number
+    )))
 }
@@ -1,7 +1,10 @@
 use color_eyre::Result;
 
+use std::any::Any;
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use crate::streams::{Input, Output};
 use crate::token::Token;
 
 #[derive(Clone, Debug)]
@@ -9,13 +12,161 @@ pub enum Argument {
     // Invocation(Invocation), // TODO
     KfkString(String),
     Number(f64),
+    Integer(i64),
+    // integers that don't fit into an i64
+    #[cfg(feature = "bigint")]
+    BigInt(num_bigint::BigInt),
+    Bool(bool),
+    Nil,
+    List(Vec<Self>),
+    // key value pairs in insertion order, keys are unique
+    Map(Vec<(Self, Self)>),
+    Handle(Handle),
+}
+
+// a value of the host, e.g. a file or a connection, that scripts can store and pass
+// to native keywords but can't look into
+#[derive(Clone)]
+pub struct Handle {
+    value: Arc<dyn Any + Send + Sync>,
+    type_name: &'static str,
+}
+
+impl Handle {
+    pub fn new<T: Any + Send + Sync>(value: T) -> Self {
+        Self {
+            value: Arc::new(value),
+            type_name: std::any::type_name::<T>(),
+        }
+    }
+
+    #[must_use]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref()
+    }
+
+    #[must_use]
+    pub fn downcast<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        Arc::clone(&self.value).downcast().ok()
+    }
+
+    #[must_use]
+    pub const fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+// handles are the same if they share the same value, not if their values are equal
+impl PartialEq for Handle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.value, &other.value)
+    }
+}
+
+impl std::hash::Hash for Handle {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.value).cast::<()>().hash(state);
+    }
+}
+
+impl std::fmt::Debug for Handle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Handle").field(&self.type_name).finish()
+    }
+}
+
+impl Argument {
+    #[must_use]
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Self::KfkString(_) => "String",
+            Self::Number(_) => "Number",
+            Self::Integer(_) => "Integer",
+            #[cfg(feature = "bigint")]
+            Self::BigInt(_) => "Integer",
+            Self::Bool(_) => "Bool",
+            Self::Nil => "Nil",
+            Self::List(_) => "List",
+            Self::Map(_) => "Map",
+            Self::Handle(_) => "Handle",
+        }
+    }
+
+    // the one rule if, while, !, and and or decide by: false, nil, zero and empty
+    // strings, lists and maps don't hold, everything else does
+    #[must_use]
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Self::KfkString(s) => !s.is_empty(),
+            Self::Number(n) => n.abs() >= 10e-9,
+            Self::Integer(i) => *i != 0,
+            #[cfg(feature = "bigint")]
+            Self::BigInt(i) => *i != num_bigint::BigInt::ZERO,
+            Self::Bool(b) => *b,
+            Self::Nil => false,
+            Self::List(l) => !l.is_empty(),
+            Self::Map(m) => !m.is_empty(),
+            Self::Handle(_) => true,
+        }
+    }
+}
+
+// the integer a float is exactly equal to, if there is one. Comparing as floats
+// would make integers that differ by less than the precision of a float equal
+#[allow(clippy::cast_possible_truncation)]
+fn whole_number_as_i64(number: f64) -> Option<i64> {
+    // 2^63, the first float too large for an i64
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+    (number.fract() == 0.0 && (-LIMIT..LIMIT).contains(&number)).then_some(number as i64)
+}
+
+// whole numbers are hashed like the integer they are equal to, their own Display
+// output is rounded and would hash equal values differently
+fn number_hash_key(number: f64) -> String {
+    if let Some(integer) = whole_number_as_i64(number) {
+        return integer.to_string();
+    }
+    #[cfg(feature = "bigint")]
+    if number.fract() == 0.0 {
+        if let Some(integer) = <num_bigint::BigInt as num_traits::FromPrimitive>::from_f64(number) {
+            return integer.to_string();
+        }
+    }
+    number.to_string()
 }
 
 impl std::hash::Hash for Argument {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         match self {
             Self::KfkString(l0) => format!("KfkString:{l0}").hash(state),
-            Self::Number(l0) => format!("Number:{l0}").hash(state),
+            Self::Number(l0) => format!("Number:{}", number_hash_key(*l0)).hash(state),
+            // hashed like a number because an integer equals the number of same value
+            Self::Integer(l0) => format!("Number:{l0}").hash(state),
+            #[cfg(feature = "bigint")]
+            Self::BigInt(l0) => format!("Number:{l0}").hash(state),
+            Self::Bool(l0) => format!("Bool:{l0}").hash(state),
+            Self::Nil => "Nil".hash(state),
+            Self::List(l0) => {
+                "List".hash(state);
+                l0.hash(state);
+            }
+            // maps with the same entries in a different order are equal, so the
+            // hashes of their entries are combined in an order independent way
+            Self::Map(m0) => {
+                "Map".hash(state);
+                m0.iter()
+                    .map(|entry| {
+                        let mut entry_hasher = std::collections::hash_map::DefaultHasher::new();
+                        entry.hash(&mut entry_hasher);
+                        std::hash::Hasher::finish(&entry_hasher)
+                    })
+                    .fold(0, u64::wrapping_add)
+                    .hash(state);
+            }
+            Self::Handle(h0) => {
+                "Handle".hash(state);
+                h0.hash(state);
+            }
         }
     }
 }
@@ -27,6 +178,32 @@ impl std::fmt::Display for Argument {
         match self {
             Self::KfkString(s) => write!(f, "'{s}\""),
             Self::Number(n) => write!(f, "{n}"),
+            Self::Integer(i) => write!(f, "{i}"),
+            #[cfg(feature = "bigint")]
+            Self::BigInt(i) => write!(f, "{i}"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Nil => write!(f, "nil"),
+            Self::List(l) => {
+                write!(f, "[")?;
+                for (index, item) in l.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Self::Map(m) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in m.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key}: {value}")?;
+                }
+                write!(f, "}}")
+            }
+            Self::Handle(h) => write!(f, "<handle {}>", h.type_name()),
         }
     }
 }
@@ -35,17 +212,61 @@ impl PartialEq for Argument {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::KfkString(l0), Self::KfkString(r0)) => l0 == r0,
-            (Self::Number(l0), Self::Number(r0)) => (l0 - r0).abs() > 10e-9,
+            (Self::Number(l0), Self::Number(r0)) => (l0 - r0).abs() < 10e-9,
+            (Self::Integer(l0), Self::Integer(r0)) => l0 == r0,
+            (Self::Integer(i), Self::Number(n)) | (Self::Number(n), Self::Integer(i)) => {
+                whole_number_as_i64(*n) == Some(*i)
+            }
+            #[cfg(feature = "bigint")]
+            (Self::BigInt(l0), Self::BigInt(r0)) => l0 == r0,
+            #[cfg(feature = "bigint")]
+            (Self::BigInt(b), Self::Integer(i)) | (Self::Integer(i), Self::BigInt(b)) => {
+                *b == num_bigint::BigInt::from(*i)
+            }
+            #[cfg(feature = "bigint")]
+            (Self::BigInt(b), Self::Number(n)) | (Self::Number(n), Self::BigInt(b)) => {
+                n.fract() == 0.0 && num_traits::FromPrimitive::from_f64(*n).as_ref() == Some(b)
+            }
+            (Self::Bool(l0), Self::Bool(r0)) => l0 == r0,
+            (Self::Nil, Self::Nil) => true,
+            (Self::List(l0), Self::List(r0)) => l0 == r0,
+            (Self::Map(m0), Self::Map(r0)) => {
+                m0.len() == r0.len() && m0.iter().all(|entry| r0.contains(entry))
+            }
+            (Self::Handle(h0), Self::Handle(h1)) => h0 == h1,
             _ => false,
         }
     }
 }
 
+// a native keyword that can capture context of the host, e.g. a connection or a counter
+pub type NativeClosure = Arc<dyn Fn(GlobalState, Vec<Argument>) -> Result<GlobalState> + Send + Sync>;
+
+#[derive(Clone)]
+pub enum Implementation {
+    Native(fn(GlobalState, Vec<Argument>) -> Result<GlobalState>),
+    Closure(NativeClosure),
+    // body of a keyword defined in kfkscript via keyword ... end
+    Script(Vec<Token>),
+}
+
+impl std::fmt::Debug for Implementation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Native(implementation) => f.debug_tuple("Native").field(implementation).finish(),
+            Self::Closure(_) => f.debug_tuple("Closure").finish_non_exhaustive(),
+            Self::Script(body) => f.debug_tuple("Script").field(body).finish(),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct KeywordImplementation {
     pub name: String,
-    pub implementation: fn(GlobalState, Vec<Argument>) -> Result<GlobalState>,
+    pub implementation: Implementation,
     pub number_of_arguments: u32,
+    pub variadic: bool,
+    pub short_circuit: Option<fn(&Argument) -> bool>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -54,6 +275,40 @@ pub enum NestingState {
     Else,
     Ignore,
     SubroutineDefinition,
+    KeywordDefinition,
+    While,
+    // the items of the list that are yet to be bound to variable
+    For {
+        variable: Argument,
+        remaining: Vec<Argument>,
+    },
+    // a loop whose current iteration is skipped because of continue
+    Continue(Box<Self>),
+}
+
+// tells main_loop to continue somewhere else than with the next token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Jump {
+    // remember the start of a while so its condition can be evaluated again
+    LoopStart,
+    // remember the start of the body of a for
+    LoopBodyStart,
+    // jump back to the start of a while and forget it
+    LoopEnd,
+    // jump back to the start of the body of a for for the next item
+    LoopRepeat,
+    // forget the start of the loop that was left
+    Break,
+    Return,
+    // stop running the code, the host decides what to do with the exit code
+    Exit(i32),
+}
+
+// what + does when it is given a string and a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixedTypes {
+    Error,
+    Cast,
 }
 
 #[derive(Clone, Debug)]
@@ -65,16 +320,39 @@ pub struct GlobalState {
     pub line_number: u32,
     pub nesting: Vec<NestingState>,
     pub subroutine_name: Option<Argument>,
+    pub keyword_definition: Option<(String, u32)>,
+    pub jump: Option<Jump>,
+    pub mixed_types: MixedTypes,
     // pub subroutine_content: Vec<InvocationArgument>,
     // pub is_keyword_definiton: bool,
-    pub ret: Option<Argument>,
+    pub ret: Argument,
     pub scopes: Vec<Scope>,
-    // pub variadic_number: u32,
+    pub output: Output,
+    pub input: Input,
+}
+
+impl Default for GlobalState {
+    fn default() -> Self {
+        Self {
+            variables: HashMap::new(),
+            keywords: HashMap::new(),
+            ret: Argument::Nil,
+            nesting: vec![],
+            line_number: 0,
+            scopes: vec![],
+            subroutines: HashMap::new(),
+            subroutine_name: None,
+            keyword_definition: None,
+            jump: None,
+            mixed_types: MixedTypes::Error,
+            output: Output::stdout(),
+            input: Input::stdin(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Scope {
     pub variables: HashMap<Argument, Argument>,
-    pub ret: Option<Argument>,
     pub line_number: u32,
 }
@@ -1,7 +1,11 @@
-use std::{iter::Peekable, slice::Iter};
+use std::{fs, iter::Peekable, path::Path, slice::Iter, sync::Arc};
 
 use crate::{
-    control_flow, expression::{Argument, GlobalState, KeywordImplementation, NestingState}, token::{self, Keyword, Token}
+    builtins, control_flow,
+    expression::{Argument, GlobalState, Implementation, Jump, KeywordImplementation, NestingState},
+    keywords, parser,
+    streams::{Input, Output},
+    token::{self, Keyword, Token},
 };
 use color_eyre::{
     eyre::{eyre, OptionExt},
@@ -17,115 +21,409 @@ pub fn run_next_expression(
       Token::Keyword(keyword) => keyword,
       Token::KfkString(s) => Err(eyre!(format!("expected keyword, got string '{}\" in line {}", s.lexem, s.line_number)))?,
       Token::Number(n) => Err(eyre!(format!("expected keyword, got number {} in line {}", n.number, n.line_number)))?,
+      Token::Integer(i) => Err(eyre!(format!("expected keyword, got integer {} in line {}", i.integer, i.line_number)))?,
+      #[cfg(feature = "bigint")]
+      Token::BigInt(i) => Err(eyre!(format!("expected keyword, got integer {} in line {}", i.integer, i.line_number)))?,
   };
     new_state.line_number = keyword.line_number;
 
-    let keyword_impl = global_state
-        .keywords
-        .get(&keyword.lexem)
-        .ok_or_eyre(format!(
-            "keyword {} not implemented in line {}",
-            keyword.lexem, keyword.line_number
-        ))?;
+    let (keyword_impl, number_of_arguments) = resolve_keyword(keyword, global_state)?;
     // let args = vec![InvocationArgument::KfkString(token::KfkString{ lexem: "string".into(), line_number: 42, }), InvocationArgument::Number(Number{ lexem: "42".into(), number: 42.0, line_number: 42 })];
     let args: Vec<Argument>;
-    (args, new_state) = retrieve_arguments(keyword_impl, keyword, tokens, new_state)?;
+    (args, new_state) =
+        retrieve_arguments(keyword_impl, number_of_arguments, keyword, tokens, new_state)?;
+    if leaves_code(&new_state) {
+        return Ok(new_state);
+    }
+
+    match &keyword_impl.implementation {
+        Implementation::Native(implementation) => implementation(new_state, args),
+        Implementation::Closure(implementation) => implementation(new_state, args),
+        Implementation::Script(body) => run_with_arguments(body, new_state, args),
+    }
+}
 
-    // println!("PIZZA {:?}, {:?}", keyword, args);
-    (keyword_impl.implementation)(new_state, args)
+// variadic keywords can be called with any number of arguments by appending it to
+// their name, e.g. println@3 'a" 'b" 'c". Without it they take their default number
+fn resolve_keyword<'a>(
+    keyword: &Keyword,
+    global_state: &'a GlobalState,
+) -> Result<(&'a KeywordImplementation, u32)> {
+    if let Some(keyword_impl) = global_state.keywords.get(&keyword.lexem) {
+        return Ok((keyword_impl, keyword_impl.number_of_arguments));
+    }
+    let not_implemented = format!(
+        "keyword {} not implemented in line {}",
+        keyword.lexem, keyword.line_number
+    );
+    let (name, number_of_arguments) = keyword
+        .lexem
+        .rsplit_once('@')
+        .ok_or_eyre(not_implemented.clone())?;
+    let keyword_impl = global_state
+        .keywords
+        .get(name)
+        .ok_or_eyre(not_implemented)?;
+    let number_of_arguments = number_of_arguments.parse().map_err(|_| {
+        eyre!(format!(
+            "invalid number of arguments {number_of_arguments} for keyword {name} in line {}",
+            keyword.line_number
+        ))
+    })?;
+    if !keyword_impl.variadic {
+        Err(eyre!(format!(
+            "keyword {name} does not take a variable number of arguments in line {}",
+            keyword.line_number
+        )))?;
+    }
+    Ok((keyword_impl, number_of_arguments))
+}
+
+// keywords defined in kfkscript and subroutines called by the host run in a fresh
+// scope where their arguments are bound to $args@1 to $args@N
+fn run_with_arguments(
+    body: &[Token],
+    global_state: GlobalState,
+    args: Vec<Argument>,
+) -> Result<GlobalState> {
+    let mut new_state = keywords::scope_push(global_state, vec![])?;
+    for (index, arg) in args.into_iter().enumerate() {
+        new_state
+            .variables
+            .insert(Argument::KfkString(format!("args@{}", index + 1)), arg);
+    }
+    new_state = run_body(body, new_state)?;
+    keywords::scope_pop(new_state, vec![])
+}
+
+// bodies of subroutines and keywords get their own nesting stack so a loop or if
+// of the caller can't be continued, broken out of or ended from within them.
+// Leaving a body early with return also pops the scopes it pushed
+pub fn run_body(body: &[Token], global_state: GlobalState) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let outer_nesting = std::mem::take(&mut new_state.nesting);
+    let scope_depth = new_state.scopes.len();
+    new_state = main_loop(body.iter().peekable(), new_state)?;
+    new_state.nesting = outer_nesting;
+    if new_state.jump == Some(Jump::Return) {
+        new_state.jump = None;
+        while new_state.scopes.len() > scope_depth {
+            new_state = keywords::scope_pop(new_state, vec![])?;
+        }
+    }
+    Ok(new_state)
+}
+
+// an exit or return in an argument, e.g. in a keyword defined in kfkscript, stops
+// the keyword it is an argument of from being called
+const fn leaves_code(global_state: &GlobalState) -> bool {
+    matches!(global_state.jump, Some(Jump::Return | Jump::Exit(_)))
 }
 
 fn retrieve_arguments(
     keyword_impl: &KeywordImplementation,
+    number_of_arguments: u32,
     keyword: &Keyword,
     tokens: &mut Peekable<Iter<Token>>,
     global_state: GlobalState,
 ) -> Result<(Vec<Argument>, GlobalState)> {
     let mut new_state = global_state;
-    let args = (0..keyword_impl.number_of_arguments).map(|_| {
-      Ok(match tokens.peek().ok_or_eyre(format!(
-          "Not enough arguments supplied to keyword {} in line {}",
-          keyword_impl.name, keyword.line_number
-      ))? {
-          Token::Keyword(_) => {
-              new_state = run_next_expression(tokens, &new_state.clone())?;
-              new_state.ret.clone().ok_or_eyre(format!("Return value is None in line {}. This error should never surface, please inform the developers of kfkscript", new_state.line_number))?
-          }
-          Token::KfkString(arg) => {
-              tokens.next();
-              new_state.line_number = arg.line_number;
-              Argument::KfkString(arg.lexem.clone())
-          }
-          Token::Number(arg) => {
-              tokens.next();
-              new_state.line_number = arg.line_number;
-              Argument::Number(arg.number)
-          }
-      })
-  }).collect::<Result<Vec<Argument>>>()?;
+    let mut args = vec![];
+    for argument_index in 0..number_of_arguments {
+        let arg = match tokens.peek().ok_or_eyre(format!(
+            "Not enough arguments supplied to keyword {} in line {}",
+            keyword_impl.name, keyword.line_number
+        ))? {
+            Token::Keyword(_) => {
+                new_state = run_next_expression(tokens, &new_state.clone())?;
+                if leaves_code(&new_state) {
+                    return Ok((args, new_state));
+                }
+                new_state.ret.clone()
+            }
+            Token::KfkString(arg) => {
+                tokens.next();
+                new_state.line_number = arg.line_number;
+                Argument::KfkString(arg.lexem.clone())
+            }
+            Token::Number(arg) => {
+                tokens.next();
+                new_state.line_number = arg.line_number;
+                Argument::Number(arg.number)
+            }
+            Token::Integer(arg) => {
+                tokens.next();
+                new_state.line_number = arg.line_number;
+                Argument::Integer(arg.integer)
+            }
+            #[cfg(feature = "bigint")]
+            Token::BigInt(arg) => {
+                tokens.next();
+                new_state.line_number = arg.line_number;
+                Argument::BigInt(arg.integer.clone())
+            }
+        };
+        let short_circuits = keyword_impl
+            .short_circuit
+            .is_some_and(|short_circuit| short_circuit(&arg));
+        args.push(arg);
+        if short_circuits {
+            for _ in argument_index + 1..number_of_arguments {
+                skip_expression(tokens, &new_state)?;
+            }
+            break;
+        }
+    }
     Ok((args, new_state))
 }
 
-pub fn get_subroutine_tokens(
+// moves past the next expression without evaluating it, including the arguments
+// of its keyword
+fn skip_expression(tokens: &mut Peekable<Iter<Token>>, global_state: &GlobalState) -> Result<()> {
+    let token = tokens.next().ok_or_eyre(format!(
+        "Not enough arguments supplied in line {}",
+        global_state.line_number
+    ))?;
+    if let Token::Keyword(keyword) = token {
+        let (_, number_of_arguments) = resolve_keyword(keyword, global_state)?;
+        for _ in 0..number_of_arguments {
+            skip_expression(tokens, global_state)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn get_definition_tokens(
     token_iter: &mut Peekable<Iter<Token>>,
     global_state: GlobalState,
-) -> Result<(Vec<Token>,GlobalState)> {
-    let mut subroutine_tokens  = vec![];
+) -> Result<(Vec<Token>, GlobalState)> {
+    let mut definition_tokens = vec![];
     let mut new_state = global_state;
     while new_state
         .nesting
         .contains(&NestingState::SubroutineDefinition)
+        || new_state.nesting.contains(&NestingState::KeywordDefinition)
     {
-        let next_token = token_iter
-            .next()
-            .ok_or_eyre(format!(
-                "no end found to terminate subroutine definition in line {}",
-                new_state.line_number
-            ))?;
-        subroutine_tokens.push(next_token.clone());
+        let next_token = token_iter.next().ok_or_eyre(format!(
+            "no end found to terminate definition in line {}",
+            new_state.line_number
+        ))?;
+        definition_tokens.push(next_token.clone());
         if let Token::Keyword(keyword) = next_token {
-            if keyword.lexem == "if" {
+            if control_flow::is_block_start(&keyword.lexem) {
                 new_state.nesting.push(NestingState::Ignore);
             } else if keyword.lexem == "end" {
                 new_state.nesting.pop();
             }
         }
     }
-    subroutine_tokens.pop(); // get rid of the superflous end
-    Ok((subroutine_tokens, new_state))
+    definition_tokens.pop(); // get rid of the superflous end
+    Ok((definition_tokens, new_state))
 }
 
-pub fn main_loop(mut token_iter: std::iter::Peekable<std::slice::Iter<token::Token>>, global_state: GlobalState) -> Result<GlobalState> {
+fn define_keyword(
+    global_state: GlobalState,
+    name: String,
+    number_of_arguments: u32,
+    body: Vec<Token>,
+) -> GlobalState {
     let mut new_state = global_state;
-    while let Some(next_token) = token_iter.clone().peek() {
-        // let tokens_to_skip: u32;
-        if let Some(nesting) = new_state.nesting.last() {
-            if nesting == &NestingState::Else {
-                new_state = control_flow::skip_tokens(&mut token_iter, new_state)?;
-                continue;
-                if let Token::Keyword(kw) = next_token {
-                    if kw.lexem == "end" || kw.lexem == "else" {
-                        println!("foo {:?}", kw.lexem);
-                        // (tokens_to_skip, new_state) =
-                        // control_flow::hurenmüll(&mut token_iter, new_state)?;
-                        // new_state = control_flow::skip_tokens(&mut token_iter, new_state)?;
-                    // if tokens_to_skip > 0 {
-                    //     for _ in 0..=tokens_to_skip {
-                    //         token_iter.next();
-                    //     }
-                    // }
-                    }
-                }
-            }
+    new_state.keywords.insert(
+        name.clone(),
+        KeywordImplementation {
+            name,
+            implementation: Implementation::Script(body),
+            number_of_arguments,
+            variadic: false,
+            short_circuit: None,
+        },
+    );
+    new_state
+}
+
+pub fn main_loop(
+    mut token_iter: Peekable<Iter<token::Token>>,
+    global_state: GlobalState,
+) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let mut loop_starts = vec![];
+    while token_iter.peek().is_some() {
+        if matches!(
+            new_state.nesting.last(),
+            Some(NestingState::Else | NestingState::Ignore | NestingState::Continue(_))
+        ) {
+            // stops right before the else or end that has to be run next
+            new_state = control_flow::skip_tokens(&mut token_iter, new_state)?;
         }
 
+        let expression_start = token_iter.clone();
         new_state = run_next_expression(&mut token_iter, &new_state)?;
+        match new_state.jump.take() {
+            Some(Jump::LoopStart) => loop_starts.push(expression_start),
+            Some(Jump::LoopBodyStart) => loop_starts.push(token_iter.clone()),
+            Some(Jump::LoopEnd) => {
+                token_iter = loop_starts.pop().ok_or_eyre(format!("No while loop to jump back to in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?;
+            }
+            Some(Jump::LoopRepeat) => {
+                token_iter = loop_starts.last().cloned().ok_or_eyre(format!("No for loop to jump back to in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?;
+            }
+            Some(Jump::Break) => {
+                loop_starts.pop();
+            }
+            Some(jump @ (Jump::Return | Jump::Exit(_))) => {
+                new_state.jump = Some(jump);
+                return Ok(new_state);
+            }
+            None => {}
+        }
         if let Some(subroutine_name) = new_state.subroutine_name.clone() {
             let subroutine_tokens: Vec<Token>;
-            (subroutine_tokens, new_state) = get_subroutine_tokens(&mut token_iter, new_state)?;
+            (subroutine_tokens, new_state) = get_definition_tokens(&mut token_iter, new_state)?;
             new_state.subroutines.insert(subroutine_name, subroutine_tokens);
             new_state.subroutine_name = None;
         }
+        if let Some((keyword_name, number_of_arguments)) = new_state.keyword_definition.take() {
+            let keyword_tokens: Vec<Token>;
+            (keyword_tokens, new_state) = get_definition_tokens(&mut token_iter, new_state)?;
+            new_state = define_keyword(new_state, keyword_name, number_of_arguments, keyword_tokens);
+        }
     }
     Ok(new_state)
 }
+
+// runs kfkscript from within a Rust program. The state is kept from one call of
+// eval to the next, so variables, subroutines and keywords defined by one script
+// can be used by the following ones
+#[derive(Clone, Debug)]
+pub struct Interpreter {
+    state: GlobalState,
+    exit_code: Option<i32>,
+}
+
+impl Interpreter {
+    pub fn new() -> Result<Self> {
+        Self::with_state(GlobalState::default())
+    }
+
+    // registers the builtin keywords on top of an existing state, e.g. one with
+    // a different GlobalState::mixed_types
+    pub fn with_state(global_state: GlobalState) -> Result<Self> {
+        Ok(Self {
+            state: builtins::register_builtin_keywords(global_state)?,
+            exit_code: None,
+        })
+    }
+
+    // unlike the builtin keywords, the implementation can be a closure that
+    // captures context of the host
+    pub fn register_keyword(
+        &mut self,
+        name: &str,
+        implementation: impl Fn(GlobalState, Vec<Argument>) -> Result<GlobalState> + Send + Sync + 'static,
+        number_of_arguments: u32,
+    ) -> Result<()> {
+        self.state = builtins::register_closure_keyword(
+            self.state.clone(),
+            name,
+            Arc::new(implementation),
+            number_of_arguments,
+        )?;
+        Ok(())
+    }
+
+    // returns what the last expression of the code returned, or nil. If the code
+    // fails the state is left as it was before
+    pub fn eval(&mut self, code: &str) -> Result<Argument> {
+        self.eval_tokens(&parser::parse(code)?)
+    }
+
+    // like eval, for code that is parsed already
+    pub fn eval_tokens(&mut self, tokens: &[Token]) -> Result<Argument> {
+        let mut new_state = self.state.clone();
+        new_state.ret = Argument::Nil;
+        new_state = main_loop(tokens.iter().peekable(), new_state)?;
+        self.finish(new_state)
+    }
+
+    fn finish(&mut self, global_state: GlobalState) -> Result<Argument> {
+        let mut new_state = global_state;
+        match new_state.jump.take() {
+            // a return outside of a subroutine or keyword ends the code early, an exit
+            // ends it for good. Both leave the blocks they are in unterminated
+            Some(Jump::Return) => new_state.nesting.clear(),
+            Some(Jump::Exit(exit_code)) => {
+                self.exit_code = Some(exit_code);
+                new_state.nesting.clear();
+            }
+            _ if !new_state.nesting.is_empty() => {
+                Err(eyre!("Block not terminated by end at the end of the code"))?;
+            }
+            _ => {}
+        }
+        self.state = new_state;
+        Ok(self.state.ret.clone())
+    }
+
+    // runs a subroutine defined by an earlier eval with its arguments bound like
+    // those of a keyword defined in kfkscript and returns what it returned
+    pub fn call(&mut self, name: &str, args: Vec<Argument>) -> Result<Argument> {
+        let name = Argument::KfkString(name.into());
+        let body = self
+            .state
+            .subroutines
+            .get(&name)
+            .ok_or_eyre(format!("Subroutine {name} not found"))?
+            .clone();
+        let mut new_state = self.state.clone();
+        new_state.ret = Argument::Nil;
+        new_state = run_with_arguments(&body, new_state, args)?;
+        self.finish(new_state)
+    }
+
+    // makes command line arguments available to scripts as the list $argv and
+    // their number as $argc
+    pub fn set_arguments(&mut self, arguments: Vec<String>) {
+        self.state.variables.insert(
+            Argument::KfkString("argc".into()),
+            keywords::number_from_usize(arguments.len()),
+        );
+        self.state.variables.insert(
+            Argument::KfkString("argv".into()),
+            Argument::List(arguments.into_iter().map(Argument::KfkString).collect()),
+        );
+    }
+
+    pub fn set_output(&mut self, output: Output) {
+        self.state.output = output;
+    }
+
+    pub fn set_input(&mut self, input: Input) {
+        self.state.input = input;
+    }
+
+    pub fn eval_file(&mut self, path: impl AsRef<Path>) -> Result<Argument> {
+        let code = fs::read_to_string(path)?;
+        self.eval(&code)
+    }
+
+    // the code given to the keyword exit by a script, the host decides what to do
+    // with it. Later code can still be run
+    #[must_use]
+    pub const fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> &GlobalState {
+        &self.state
+    }
+
+    pub const fn state_mut(&mut self) -> &mut GlobalState {
+        &mut self.state
+    }
+
+    #[must_use]
+    pub fn into_state(self) -> GlobalState {
+        self.state
+    }
+}
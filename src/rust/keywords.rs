@@ -1,62 +1,443 @@
+use std::any::Any;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use color_eyre::eyre::{eyre, OptionExt};
 use color_eyre::Result;
+#[cfg(feature = "bigint")]
+use num_bigint::BigInt;
+#[cfg(feature = "bigint")]
+use num_traits::ToPrimitive;
 
-use crate::expression::{self, Argument, GlobalState, Scope};
+use crate::expression::{self, Argument, GlobalState, MixedTypes, Scope};
 
-pub use crate::control_flow::{else_, end, if_};
+pub use crate::control_flow::{break_, continue_, else_, end, for_, if_, while_};
+#[cfg(feature = "bigint")]
+use crate::bigint;
 use crate::interpreter;
 use crate::parser::print_tokens;
+use crate::strings::stringify;
 
-#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+#[allow(clippy::needless_pass_by_value)]
 pub fn println(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
     let mut print_string = String::new();
     let mut arg_iter = args.iter().peekable();
     while let Some(current_arg) = arg_iter.next() {
-        let stringified_arg = match current_arg {
-            expression::Argument::Number(number) => number.to_string(),
-            expression::Argument::KfkString(string) => string.clone(),
-        };
-        print_string.push_str(&stringified_arg);
+        print_string.push_str(&stringify(current_arg));
         if arg_iter.peek().is_some() {
             print_string.push(' ');
         }
     }
-    println!("{print_string}");
-    Ok(global_state)
+    writeln!(global_state.output.lock()?, "{print_string}")?;
+    // println doesn't return what it printed
+    let mut new_state = global_state;
+    new_state.ret = Argument::Nil;
+    Ok(new_state)
+}
+
+// the next line of input without its line break, or nil at the end of the input
+#[allow(clippy::needless_pass_by_value)]
+pub fn readln(global_state: GlobalState, _args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    new_state.ret = new_state
+        .input
+        .read_line()?
+        .map_or(Argument::Nil, Argument::KfkString);
+    Ok(new_state)
 }
 
+// adds numbers and concatenates strings. A string and another type are either rejected
+// or concatenated after casting the other one, depending on GlobalState::mixed_types
 pub fn add(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
     let sum = args
         .into_iter()
+        .map(Ok)
+        .reduce(|a, b| match (a?, b?) {
+            (a, b) if is_numeric(&a) && is_numeric(&b) => Ok(arithmetic(&a, &b, &ADD)),
+            (Argument::KfkString(a), Argument::KfkString(b)) => Ok(Argument::KfkString(a + &b)),
+            (a, b) if matches!(a, Argument::KfkString(_)) || matches!(b, Argument::KfkString(_)) => match global_state.mixed_types {
+                MixedTypes::Cast => Ok(Argument::KfkString(stringify(&a) + &stringify(&b))),
+                MixedTypes::Error => Err(eyre!(format!("cannot use arguments of type {} and {} together with keyword + in line {}, use str::cast or --implicit-cast to concatenate them", a.type_name(), b.type_name(), global_state.line_number))),
+            },
+            (a, b) => Err(eyre!(format!("cannot use arguments of type {} and {} together with keyword + in line {}", a.type_name(), b.type_name(), global_state.line_number))),
+        })
+        .ok_or_eyre(format!("No arguments supplied to keyword + in line {}", global_state.line_number))??;
+    let mut new_state = global_state;
+    new_state.ret = sum;
+    Ok(new_state)
+}
+
+pub fn nth_argument<'a>(args: &'a [Argument], index: usize, keyword: &str, line_number: u32) -> Result<&'a Argument> {
+    args.get(index).ok_or_eyre(format!("Argument {} of {keyword} not found in line {line_number}. This error should never surface, please inform the developers of Kfkscript.", index + 1))
+}
+
+pub fn as_string(arg: &Argument, keyword: &str, line_number: u32) -> Result<String> {
+    match arg {
+        Argument::KfkString(string) => Ok(string.clone()),
+        arg => Err(eyre!(format!("cannot use argument of type {} with keyword {keyword} in line {line_number}", arg.type_name()))),
+    }
+}
+
+pub fn as_index(arg: &Argument, keyword: &str, line_number: u32) -> Result<usize> {
+    match arg {
+        Argument::Integer(i) if *i >= 0 => usize::try_from(*i).map_err(|_| eyre!(format!("index {i} too large for keyword {keyword} in line {line_number}"))),
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Argument::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+        Argument::Integer(_) | Argument::Number(_) => Err(eyre!(format!("expected a non-negative whole number for keyword {keyword}, got {arg} in line {line_number}"))),
+        #[cfg(feature = "bigint")]
+        Argument::BigInt(i) => i.to_usize().ok_or_else(|| eyre!(format!("index {arg} too large for keyword {keyword} in line {line_number}"))),
+        arg => Err(eyre!(format!("cannot use argument of type {} with keyword {keyword} in line {line_number}", arg.type_name()))),
+    }
+}
+
+// the value of a handle the host passed to the script, if it is a T
+pub fn handle_argument<T: Any + Send + Sync>(args: &[Argument], index: usize, keyword: &str, line_number: u32) -> Result<Arc<T>> {
+    match nth_argument(args, index, keyword, line_number)? {
+        Argument::Handle(handle) => handle.downcast().ok_or_eyre(format!("cannot use handle of type {} with keyword {keyword} in line {line_number}, expected {}", handle.type_name(), std::any::type_name::<T>())),
+        arg => Err(eyre!(format!("cannot use argument of type {} with keyword {keyword} in line {line_number}", arg.type_name()))),
+    }
+}
+
+pub fn string_argument(args: &[Argument], index: usize, keyword: &str, line_number: u32) -> Result<String> {
+    as_string(nth_argument(args, index, keyword, line_number)?, keyword, line_number)
+}
+
+pub fn index_argument(args: &[Argument], index: usize, keyword: &str, line_number: u32) -> Result<usize> {
+    as_index(nth_argument(args, index, keyword, line_number)?, keyword, line_number)
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub fn number_from_usize(n: usize) -> Argument {
+    i64::try_from(n).map_or(Argument::Number(n as f64), Argument::Integer)
+}
+
+#[must_use]
+pub const fn is_numeric(arg: &Argument) -> bool {
+    match arg {
+        Argument::Integer(_) | Argument::Number(_) => true,
+        #[cfg(feature = "bigint")]
+        Argument::BigInt(_) => true,
+        _ => false,
+    }
+}
+
+// can only be const without the bigint feature
+#[cfg_attr(not(feature = "bigint"), allow(clippy::missing_const_for_fn))]
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn as_float(arg: &Argument) -> Option<f64> {
+    match arg {
+        Argument::Integer(i) => Some(*i as f64),
+        Argument::Number(n) => Some(*n),
+        #[cfg(feature = "bigint")]
+        Argument::BigInt(i) => Some(bigint::to_f64(i)),
+        _ => None,
+    }
+}
+
+// how an arithmetic keyword combines two numbers. The integer operations return
+// None if they can't give an exact result
+pub struct Operation {
+    pub integer: fn(i64, i64) -> Option<i64>,
+    #[cfg(feature = "bigint")]
+    pub bigint: fn(&BigInt, &BigInt) -> Option<BigInt>,
+    pub float: fn(f64, f64) -> f64,
+}
+
+pub const ADD: Operation = Operation {
+    integer: i64::checked_add,
+    #[cfg(feature = "bigint")]
+    bigint: |a, b| Some(a + b),
+    float: |a, b| a + b,
+};
+
+pub const SUBTRACT: Operation = Operation {
+    integer: i64::checked_sub,
+    #[cfg(feature = "bigint")]
+    bigint: |a, b| Some(a - b),
+    float: |a, b| a - b,
+};
+
+pub const MULTIPLY: Operation = Operation {
+    integer: i64::checked_mul,
+    #[cfg(feature = "bigint")]
+    bigint: |a, b| Some(a * b),
+    float: |a, b| a * b,
+};
+
+// integers that don't divide evenly give a float
+pub const DIVIDE: Operation = Operation {
+    integer: |a, b| a.checked_rem(b).filter(|remainder| *remainder == 0).and_then(|_| a.checked_div(b)),
+    #[cfg(feature = "bigint")]
+    bigint: bigint::divide,
+    float: |a, b| a / b,
+};
+
+// the remainder of a division by -1 is 0 even where the division itself overflows
+pub const MODULO: Operation = Operation {
+    integer: |a, b| if b == -1 { Some(0) } else { a.checked_rem(b) },
+    #[cfg(feature = "bigint")]
+    bigint: bigint::modulo,
+    float: |a, b| a % b,
+};
+
+// integers to a negative power give a float
+pub const POWER: Operation = Operation {
+    integer: |a, b| u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+    #[cfg(feature = "bigint")]
+    bigint: bigint::power,
+    float: f64::powf,
+};
+
+// two integers stay exact as long as the integer operation can give an exact
+// result. If it overflows they become arbitrary-precision integers with the
+// bigint feature and floats without it
+#[must_use]
+pub fn arithmetic(a: &Argument, b: &Argument, operation: &Operation) -> Argument {
+    if let (Argument::Integer(a), Argument::Integer(b)) = (a, b) {
+        if let Some(result) = (operation.integer)(*a, *b) {
+            return Argument::Integer(result);
+        }
+    }
+    #[cfg(feature = "bigint")]
+    if let (Some(a), Some(b)) = (bigint::as_bigint(a), bigint::as_bigint(b)) {
+        if let Some(result) = (operation.bigint)(&a, &b) {
+            return bigint::normalize(result);
+        }
+    }
+    Argument::Number((operation.float)(
+        as_float(a).unwrap_or(f64::NAN),
+        as_float(b).unwrap_or(f64::NAN),
+    ))
+}
+
+// integers and floats as they are, anything else is rejected
+pub fn numeric_arguments(args: Vec<Argument>, keyword: &str, line_number: u32) -> Result<Vec<Argument>> {
+    args.into_iter()
         .map(|current_arg| match current_arg {
-            expression::Argument::Number(number) => Ok(number),
-            expression::Argument::KfkString(_) => Err(eyre!(format!("cannot use argument of type String with keyword + in line {}", global_state.line_number))),
-        }).collect::<Result<Vec<f64>>>()?
+            arg if is_numeric(&arg) => Ok(arg),
+            arg => Err(eyre!(format!("cannot use argument of type {} with keyword {keyword} in line {line_number}", arg.type_name()))),
+        })
+        .collect()
+}
+
+// integers converted to floats, anything else is rejected
+pub fn float_arguments(args: &[Argument], keyword: &str, line_number: u32) -> Result<Vec<f64>> {
+    args.iter()
+        .map(|current_arg| as_float(current_arg).ok_or_eyre(format!("cannot use argument of type {} with keyword {keyword} in line {line_number}", current_arg.type_name())))
+        .collect()
+}
+
+fn reduce_numbers(
+    global_state: GlobalState,
+    args: Vec<Argument>,
+    keyword: &str,
+    operation: &Operation,
+) -> Result<GlobalState> {
+    let result = numeric_arguments(args, keyword, global_state.line_number)?
         .into_iter()
-        .reduce(|a, b| a + b)
-        .ok_or_eyre(format!("No enough arguments suplied to keyword + in line {}. This error should never surface, please inform the developers of Kfkscript.", global_state.line_number))?;
+        .reduce(|a, b| arithmetic(&a, &b, operation))
+        .ok_or_eyre(format!("No arguments supplied to keyword {keyword} in line {}", global_state.line_number))?;
     let mut new_state = global_state;
-    new_state.ret = Some(expression::Argument::Number(sum));
+    new_state.ret = result;
     Ok(new_state)
 }
 
+// only exactly zero, == would also take numbers close to zero for it
+fn is_zero(arg: &Argument) -> bool {
+    match arg {
+        Argument::Integer(i) => *i == 0,
+        Argument::Number(n) => *n == 0.0,
+        #[cfg(feature = "bigint")]
+        Argument::BigInt(i) => *i == BigInt::ZERO,
+        _ => false,
+    }
+}
+
+fn check_divisors(global_state: &GlobalState, args: &[Argument], operation: &str) -> Result<()> {
+    if args.iter().skip(1).any(is_zero) {
+        Err(eyre!(format!("{operation} by zero in line {}", global_state.line_number)))?;
+    }
+    Ok(())
+}
+
 pub fn subtract(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
-    let sum = args
-        .into_iter()
-        .map(|current_arg| match current_arg {
-            expression::Argument::Number(number) => Ok(number),
-            expression::Argument::KfkString(_) => Err(eyre!(format!("cannot use argument of type String with keyword - in line {}", global_state.line_number))),
-        }).collect::<Result<Vec<f64>>>()?
-        .into_iter()
-        .reduce(|a, b| a - b)
-        .ok_or_eyre(format!("No enough arguments suplied to keyword - in line {}. This error should never surface, please inform the developers of Kfkscript.", global_state.line_number))?;
+    reduce_numbers(global_state, args, "-", &SUBTRACT)
+}
+
+pub fn multiply(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    reduce_numbers(global_state, args, "*", &MULTIPLY)
+}
+
+pub fn divide(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    check_divisors(&global_state, &args, "Division")?;
+    reduce_numbers(global_state, args, "/", &DIVIDE)
+}
+
+pub fn modulo(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    check_divisors(&global_state, &args, "Modulo")?;
+    reduce_numbers(global_state, args, "%", &MODULO)
+}
+
+pub fn power(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    #[cfg(feature = "bigint")]
+    bigint::check_power(&args, global_state.line_number)?;
+    reduce_numbers(global_state, args, "**", &POWER)
+}
+
+pub fn as_integer(arg: &Argument, keyword: &str, line_number: u32) -> Result<i64> {
+    match arg {
+        Argument::Integer(i) => Ok(*i),
+        #[cfg(feature = "bigint")]
+        Argument::BigInt(i) => i.to_i64().ok_or_else(|| eyre!(format!("integer {arg} too large for keyword {keyword} in line {line_number}"))),
+        arg => Err(eyre!(format!("cannot use argument of type {} with keyword {keyword} in line {line_number}, expected an Integer", arg.type_name()))),
+    }
+}
+
+pub fn integer_argument(args: &[Argument], index: usize, keyword: &str, line_number: u32) -> Result<i64> {
+    as_integer(nth_argument(args, index, keyword, line_number)?, keyword, line_number)
+}
+
+fn integer_operation(
+    global_state: GlobalState,
+    args: &[Argument],
+    keyword: &str,
+    operation: fn(i64, i64) -> Result<i64, String>,
+) -> Result<GlobalState> {
+    let a = integer_argument(args, 0, keyword, global_state.line_number)?;
+    let b = integer_argument(args, 1, keyword, global_state.line_number)?;
+    let result = operation(a, b).map_err(|reason| {
+        eyre!(format!("{reason} in keyword {keyword} in line {}", global_state.line_number))
+    })?;
     let mut new_state = global_state;
-    new_state.ret = Some(expression::Argument::Number(sum));
+    new_state.ret = Argument::Integer(result);
     Ok(new_state)
 }
 
+#[allow(clippy::needless_pass_by_value)]
+pub fn bit_and(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "bit::and", |a, b| Ok(a & b))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn bit_or(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "bit::or", |a, b| Ok(a | b))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn bit_xor(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "bit::xor", |a, b| Ok(a ^ b))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn bit_not(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let integer = integer_argument(&args, 0, "bit::not", global_state.line_number)?;
+    let mut new_state = global_state;
+    new_state.ret = Argument::Integer(!integer);
+    Ok(new_state)
+}
+
+fn shift_amount(b: i64) -> Result<u32, String> {
+    u32::try_from(b)
+        .ok()
+        .filter(|amount| *amount < i64::BITS)
+        .ok_or_else(|| format!("Cannot shift by {b} bits, expected 0 to {}", i64::BITS - 1))
+}
+
+// bits shifted out on the left are lost
+#[allow(clippy::needless_pass_by_value)]
+pub fn bit_shl(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "bit::shl", |a, b| Ok(a << shift_amount(b)?))
+}
+
+// shifts in the sign bit, so negative numbers stay negative
+#[allow(clippy::needless_pass_by_value)]
+pub fn bit_shr(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "bit::shr", |a, b| Ok(a >> shift_amount(b)?))
+}
+
+// rounds towards zero like % does
+pub fn integer_divide(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    if let Some(arg) = args.iter().find(|arg| !is_numeric(arg) || matches!(arg, Argument::Number(_))) {
+        Err(eyre!(format!("cannot use argument of type {} with keyword int::div in line {}, expected an Integer", arg.type_name(), global_state.line_number)))?;
+    }
+    check_divisors(&global_state, &args, "Division")?;
+    // the only overflow, the minimum divided by -1, becomes a bigint with the
+    // bigint feature and an error without it, never a float
+    let division = Operation {
+        integer: i64::checked_div,
+        #[cfg(feature = "bigint")]
+        bigint: |a, b| Some(a / b),
+        float: |_, _| f64::NAN,
+    };
+    let mut args = args.into_iter();
+    let mut quotient = args.next().ok_or_eyre(format!("No arguments supplied to keyword int::div in line {}", global_state.line_number))?;
+    for divisor in args {
+        quotient = match arithmetic(&quotient, &divisor, &division) {
+            Argument::Number(_) => Err(eyre!(format!("{} in keyword int::div in line {}", overflow(), global_state.line_number)))?,
+            result => result,
+        };
+    }
+    let mut new_state = global_state;
+    new_state.ret = quotient;
+    Ok(new_state)
+}
+
+fn gcd_of(a: i64, b: i64) -> Result<i64, String> {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    i64::try_from(a).map_err(|_| overflow())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn gcd(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "int::gcd", gcd_of)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn lcm(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "int::lcm", |a, b| {
+        if a == 0 || b == 0 {
+            return Ok(0);
+        }
+        (a / gcd_of(a, b)?)
+            .checked_mul(b)
+            .and_then(i64::checked_abs)
+            .ok_or_else(overflow)
+    })
+}
+
+fn overflow() -> String {
+    "Integer overflow".to_string()
+}
+
+// unlike +, -, * and ** these fail instead of leaving the range of an i64
+#[allow(clippy::needless_pass_by_value)]
+pub fn checked_add(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "int::checked-add", |a, b| a.checked_add(b).ok_or_else(overflow))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn checked_subtract(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "int::checked-sub", |a, b| a.checked_sub(b).ok_or_else(overflow))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn checked_multiply(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "int::checked-mul", |a, b| a.checked_mul(b).ok_or_else(overflow))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn checked_power(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    integer_operation(global_state, &args, "int::checked-pow", |a, b| {
+        let exponent = u32::try_from(b).map_err(|_| format!("Cannot raise to negative or too large power {b}"))?;
+        a.checked_pow(exponent).ok_or_else(overflow)
+    })
+}
+
 #[allow(clippy::needless_pass_by_value)]
 pub fn let_(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
     let name = args.first().ok_or_eyre(format!("Name of variable in let not found in line {}. This error should never surface, please inform the developers of Kfkscript.", global_state.line_number))?;
@@ -80,7 +461,7 @@ pub fn tel(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState
     new_state
         .variables
         .insert(name.to_owned(), value.to_owned());
-    new_state.ret = Some(value_clone);
+    new_state.ret = value_clone;
     Ok(new_state)
 }
 
@@ -89,86 +470,108 @@ pub fn eq(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState>
     let mut new_state = global_state;
     let l0 = args.first().ok_or_eyre(format!("First argument of == not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?;
     let l1 = args.get(1).ok_or_eyre(format!("Second argument of == not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?;
-    new_state.ret = Some(Argument::Number(match (l0, l1) {
-        (Argument::KfkString(s0), Argument::KfkString(s1)) => {
-            if s0 == s1 {
-                1.0
-            } else {
-                0.0
-            }
-        }
-        (Argument::Number(n0), Argument::Number(n1)) => {
-            if (n0 - n1).abs() > 10e-9 {
-                1.0
-            } else {
-                0.0
-            }
-        }
-        (_, _) => 0.0,
-    }));
+    new_state.ret = Argument::Bool(l0 == l1);
     Ok(new_state)
 }
 
+pub fn not_eq(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = eq(global_state, args)?;
+    new_state.ret = Argument::Bool(new_state.ret == Argument::Bool(false));
+    Ok(new_state)
+}
+
+// strings are compared lexicographically, a string and a number are never in order
 #[allow(clippy::needless_pass_by_value)]
-pub fn less_than(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+fn compare(
+    global_state: GlobalState,
+    args: Vec<Argument>,
+    keyword: &str,
+    in_order: fn(Ordering) -> bool,
+) -> Result<GlobalState> {
     let mut new_state = global_state;
-    let l0 = args.first().ok_or_eyre(format!("First argument of < not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?;
-    let l1 = args.get(1).ok_or_eyre(format!("Second argument of < not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?;
-    new_state.ret = Some(Argument::Number(match (l0, l1) {
-        (Argument::KfkString(s0), Argument::KfkString(s1)) => {
-            if s0 < s1 {
-                1.0
-            } else {
-                0.0
-            }
-        }
-        (Argument::Number(n0), Argument::Number(n1)) => {
-            // println!("LESS THAN {}, {}: {}", n0, n1, n0 < n1);
-            if n0 < n1 {
-                1.0
-            } else {
-                0.0
-            }
-        }
-        (_, _) => 0.0,
-    }));
+    let l0 = args.first().ok_or_eyre(format!("First argument of {keyword} not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?;
+    let l1 = args.get(1).ok_or_eyre(format!("Second argument of {keyword} not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?;
+    let ordering = match (l0, l1) {
+        (Argument::KfkString(s0), Argument::KfkString(s1)) => Some(s0.cmp(s1)),
+        (Argument::Integer(i0), Argument::Integer(i1)) => Some(i0.cmp(i1)),
+        #[cfg(feature = "bigint")]
+        (Argument::BigInt(_), Argument::Integer(_) | Argument::BigInt(_))
+        | (Argument::Integer(_), Argument::BigInt(_)) => bigint::as_bigint(l0).partial_cmp(&bigint::as_bigint(l1)),
+        (n0, n1) if is_numeric(n0) && is_numeric(n1) => as_float(n0).partial_cmp(&as_float(n1)),
+        (_, _) => None,
+    };
+    new_state.ret = Argument::Bool(ordering.is_some_and(in_order));
     Ok(new_state)
 }
 
+pub fn less_than(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    compare(global_state, args, "<", Ordering::is_lt)
+}
+
+pub fn greater_than(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    compare(global_state, args, ">", Ordering::is_gt)
+}
+
+pub fn less_or_eq(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    compare(global_state, args, "<=", Ordering::is_le)
+}
+
+pub fn greater_or_eq(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    compare(global_state, args, ">=", Ordering::is_ge)
+}
+
 #[allow(clippy::needless_pass_by_value)]
 pub fn not(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
     let mut new_state = global_state;
     let l0 = args.first().ok_or_eyre(format!("Argument of ! not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?;
-    new_state.ret = Some(Argument::Number(match l0 {
-        Argument::KfkString(s) => {
-            if s.is_empty() {
-                1.0
-            } else {
-                0.0
-            }
-        },
-        Argument::Number(n) => {
-            if n.abs() < 10e-9 {
-                1.0
-            } else {
-                0.0
-            }
-        }
-    }));
+    new_state.ret = Argument::Bool(!l0.is_truthy());
+    Ok(new_state)
+}
+
+#[must_use]
+pub fn and_short_circuits(arg: &Argument) -> bool {
+    !arg.is_truthy()
+}
+
+// only gets the arguments up to the first one that doesn't hold, see and_short_circuits
+#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+pub fn and(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    new_state.ret = Argument::Bool(args.iter().all(Argument::is_truthy));
+    Ok(new_state)
+}
+
+#[must_use]
+pub fn or_short_circuits(arg: &Argument) -> bool {
+    arg.is_truthy()
+}
+
+// only gets the arguments up to the first one that holds, see or_short_circuits
+#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+pub fn or(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    new_state.ret = Argument::Bool(args.iter().any(Argument::is_truthy));
     Ok(new_state)
 }
 
 #[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
 pub fn true_(global_state: GlobalState, _args: Vec<Argument>) -> Result<GlobalState> {
     let mut new_state = global_state;
-    new_state.ret = Some(Argument::Number(1.0));
+    new_state.ret = Argument::Bool(true);
     Ok(new_state)
 }
 
 #[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
 pub fn false_(global_state: GlobalState, _args: Vec<Argument>) -> Result<GlobalState> {
     let mut new_state = global_state;
-    new_state.ret = Some(Argument::Number(0.0));
+    new_state.ret = Argument::Bool(false);
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+pub fn nil(global_state: GlobalState, _args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    new_state.ret = Argument::Nil;
     Ok(new_state)
 }
 
@@ -177,7 +580,6 @@ pub fn scope_push(global_state: GlobalState, _args: Vec<Argument>) -> Result<Glo
     let mut new_state = global_state;
     new_state.scopes.push(Scope {
         variables: new_state.variables,
-        ret: new_state.ret.clone(),
         line_number: new_state.line_number,
     });
     new_state.variables = HashMap::new();
@@ -191,10 +593,7 @@ pub fn scope_pop(global_state: GlobalState, _args: Vec<Argument>) -> Result<Glob
         "No scope found in line {}, cannot execute scope::pop",
         new_state.line_number
     ))?;
-    // println!("POP_BEFORE {:?}", new_state.ret);
     new_state.variables = old_scope.variables;
-    //new_state.ret = old_scope.ret;
-    // println!("POP_AFTER  {:?}", new_state.ret);
     new_state.line_number = old_scope.line_number;
     Ok(new_state)
 }
@@ -209,7 +608,7 @@ pub fn scope_outer_tel(global_state: GlobalState, args: Vec<Argument>) -> Result
     let mut found = false;
     for scope in &new_state.scopes {
         if let Some(result) = scope.variables.get(search_name) {
-            new_state.ret = Some(result.to_owned());
+            new_state.ret = result.to_owned();
             found = true;
             break;
         }
@@ -239,18 +638,40 @@ pub fn scope_outer_let(global_state: GlobalState, args: Vec<Argument>) -> Result
     Ok(new_state)
 }
 
+// leaves the subroutine or keyword body it is in, including any loops and ifs in
+// it. Scopes pushed by the body with scope::push and not popped yet are popped as
+// well, so the caller gets back the scopes it had
 #[allow(clippy::needless_pass_by_value)]
 pub fn return_(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
     let mut new_state = global_state;
-    new_state.ret = Some(args.first().ok_or_eyre(
+    new_state.ret = args.first().ok_or_eyre(
         format!("First argument of return not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number)
-    )?.clone());
+    )?.clone();
+    new_state.jump = Some(expression::Jump::Return);
+    Ok(new_state)
+}
+
+// any code from 0 to 255 is allowed, including the ones the binary uses for its
+// own failures (1, 2, 65, 66 and 70). A script exiting with those is
+// indistinguishable from the binary failing, so scripts should avoid them
+#[allow(clippy::needless_pass_by_value)]
+pub fn exit(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let exit_code = integer_argument(&args, 0, "exit", global_state.line_number)?;
+    let mut new_state = global_state;
+    new_state.jump = Some(expression::Jump::Exit(
+        i32::try_from(exit_code)
+            .ok()
+            .filter(|exit_code| (0..=255).contains(exit_code))
+            .ok_or_eyre(format!("Exit code must be between 0 and 255, got {exit_code} in line {}", new_state.line_number))?,
+    ));
     Ok(new_state)
 }
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn subroutine(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
-    if global_state.nesting.contains(&expression::NestingState::SubroutineDefinition) {
+    if global_state.nesting.contains(&expression::NestingState::SubroutineDefinition)
+        || global_state.nesting.contains(&expression::NestingState::KeywordDefinition)
+    {
         Err(eyre!(format!("Nested subroutine definition not allowed in line {}", global_state.line_number)))?;
     }
     let mut new_state = global_state;
@@ -262,17 +683,46 @@ pub fn subroutine(global_state: GlobalState, args: Vec<Argument>) -> Result<Glob
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn run(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
-    let mut new_state = global_state;
+    let new_state = global_state;
     let name = args.first().ok_or_eyre(format!("Name of subroutine to run not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))?.to_owned();
     let subroutine_tokens = new_state.subroutines.get(&name).ok_or_eyre(format!("Subroutine {name} not found in line {}", new_state.line_number)).cloned()?;
     if let Ok(debug) = std::env::var("KFKSCRIPT_SUBROUTINE_DEBUG") {
         if debug == "1" {
-            println!("{name}");
-            println!("{:?}", new_state.variables);
-            print_tokens(subroutine_tokens.clone());
+            writeln!(new_state.output.lock()?, "{name}")?;
+            writeln!(new_state.output.lock()?, "{:?}", new_state.variables)?;
+            print_tokens(subroutine_tokens.clone(), &new_state.output)?;
         }
     }
-    let subroutine_iter = subroutine_tokens.iter().peekable();
-    new_state = interpreter::main_loop(subroutine_iter, new_state)?;
+    interpreter::run_body(&subroutine_tokens, new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn keyword(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    if global_state.nesting.contains(&expression::NestingState::SubroutineDefinition)
+        || global_state.nesting.contains(&expression::NestingState::KeywordDefinition)
+    {
+        Err(eyre!(format!("Nested keyword definition not allowed in line {}", global_state.line_number)))?;
+    }
+    let mut new_state = global_state;
+    let name = match args.first().ok_or_eyre(format!("Name of keyword not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))? {
+        Argument::KfkString(name) => name.clone(),
+        arg => Err(eyre!(format!("cannot use argument of type {} as name of keyword in line {}", arg.type_name(), new_state.line_number)))?,
+    };
+    let number_of_arguments = match args.get(1).ok_or_eyre(format!("Number of arguments of keyword not found in line {}. This error should never surface, please inform the developers of Kfkscript.", new_state.line_number))? {
+        Argument::Integer(i) if u32::try_from(*i).is_ok() => u32::try_from(*i)?,
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Argument::Number(n) if *n >= 0.0 && n.fract() == 0.0 && *n <= f64::from(u32::MAX) => *n as u32,
+        arg => Err(eyre!(format!("Number of arguments of keyword {name} must be a non-negative whole number, got {arg} in line {}", new_state.line_number)))?,
+    };
+    // keywords defined in kfkscript can be redefined like subroutines, e.g. by a
+    // definition in a loop body, built-in and host keywords can't
+    if new_state.keywords.get(&name).is_some_and(|keyword| !matches!(keyword.implementation, expression::Implementation::Script(_))) {
+        Err(eyre!(format!(
+            "Keyword {name} already registered in line {}. Overwriting keyword registrations is not allowed.",
+            new_state.line_number
+        )))?;
+    }
+    new_state.nesting.push(expression::NestingState::KeywordDefinition);
+    new_state.keyword_definition = Some((name, number_of_arguments));
     Ok(new_state)
 }
@@ -0,0 +1,22 @@
+// keywords report what went wrong with the line it happened in, the same way
+// for every function, so that is not repeated in a # Errors section for each
+#![allow(clippy::missing_errors_doc)]
+
+#[cfg(feature = "bigint")]
+mod bigint;
+pub mod builtins;
+pub mod control_flow;
+pub mod expression;
+pub mod interpreter;
+pub mod keywords;
+mod lists;
+mod maps;
+mod math;
+pub mod parser;
+pub mod streams;
+pub mod strings;
+pub mod token;
+
+pub use expression::{Argument, GlobalState, Handle, MixedTypes};
+pub use interpreter::Interpreter;
+pub use streams::{Buffer, Input, Output};
@@ -0,0 +1,115 @@
+use color_eyre::eyre::{eyre, OptionExt};
+use color_eyre::Result;
+
+use crate::expression::{Argument, GlobalState};
+use crate::keywords::{index_argument, nth_argument, number_from_usize};
+
+fn list_argument<'a>(args: &'a [Argument], index: usize, keyword: &str, line_number: u32) -> Result<&'a Vec<Argument>> {
+    match nth_argument(args, index, keyword, line_number)? {
+        Argument::List(items) => Ok(items),
+        arg => Err(eyre!(format!("cannot use argument of type {} with keyword {keyword} in line {line_number}", arg.type_name()))),
+    }
+}
+
+// list::push, list::pop and list::set change the list stored in a variable, so they
+// take the name of that variable instead of the list itself
+fn list_variable(global_state: &GlobalState, name: &Argument, keyword: &str) -> Result<Vec<Argument>> {
+    match global_state.variables.get(name).ok_or_eyre(format!(
+        "No such variable {name} found in line {}",
+        global_state.line_number
+    ))? {
+        Argument::List(items) => Ok(items.clone()),
+        arg => Err(eyre!(format!("cannot use variable {name} of type {} with keyword {keyword} in line {}", arg.type_name(), global_state.line_number))),
+    }
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub fn new(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    new_state.ret = Argument::List(args);
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn push(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let name = nth_argument(&args, 0, "list::push", new_state.line_number)?;
+    let value = nth_argument(&args, 1, "list::push", new_state.line_number)?;
+    let mut items = list_variable(&new_state, name, "list::push")?;
+    items.push(value.clone());
+    new_state.variables.insert(name.clone(), Argument::List(items.clone()));
+    new_state.ret = Argument::List(items);
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn pop(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let name = nth_argument(&args, 0, "list::pop", new_state.line_number)?;
+    let mut items = list_variable(&new_state, name, "list::pop")?;
+    let item = items.pop().ok_or_eyre(format!(
+        "cannot pop from empty list {name} in line {}",
+        new_state.line_number
+    ))?;
+    new_state.variables.insert(name.clone(), Argument::List(items));
+    new_state.ret = item;
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn get(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let items = list_argument(&args, 0, "list::get", new_state.line_number)?;
+    let index = index_argument(&args, 1, "list::get", new_state.line_number)?;
+    new_state.ret = items.get(index).cloned().ok_or_eyre(format!(
+        "index {index} out of range for list of length {} in line {}",
+        items.len(),
+        new_state.line_number
+    ))?;
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn set(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let name = nth_argument(&args, 0, "list::set", new_state.line_number)?;
+    let index = index_argument(&args, 1, "list::set", new_state.line_number)?;
+    let value = nth_argument(&args, 2, "list::set", new_state.line_number)?;
+    let mut items = list_variable(&new_state, name, "list::set")?;
+    let length = items.len();
+    *items.get_mut(index).ok_or_eyre(format!(
+        "index {index} out of range for list of length {length} in line {}",
+        new_state.line_number
+    ))? = value.clone();
+    new_state.variables.insert(name.clone(), Argument::List(items.clone()));
+    new_state.ret = Argument::List(items);
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn len(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let items = list_argument(&args, 0, "list::len", new_state.line_number)?;
+    new_state.ret = number_from_usize(items.len());
+    Ok(new_state)
+}
+
+// items from start up to but not including end
+#[allow(clippy::needless_pass_by_value)]
+pub fn slice(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let items = list_argument(&args, 0, "list::slice", new_state.line_number)?;
+    let start = index_argument(&args, 1, "list::slice", new_state.line_number)?;
+    let end = index_argument(&args, 2, "list::slice", new_state.line_number)?;
+    new_state.ret = Argument::List(
+        items
+            .get(start..end)
+            .ok_or_eyre(format!(
+                "cannot slice items {start} to {end} of a list of length {} in line {}",
+                items.len(),
+                new_state.line_number
+            ))?
+            .to_vec(),
+    );
+    Ok(new_state)
+}
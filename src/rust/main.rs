@@ -1,101 +1,115 @@
-use std::{collections::HashMap, fs};
-
-mod control_flow;
-mod expression;
-mod interpreter;
-mod keywords;
-mod parser;
-mod token;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read};
+use std::path::PathBuf;
 
 use clap::Parser;
-use expression::{Argument, GlobalState, KeywordImplementation};
-use parser::{parse, print_tokens};
+use kfkscript::parser::{parse, print_tokens};
+use kfkscript::{GlobalState, Interpreter, MixedTypes, Output};
+
+use color_eyre::eyre::{Report, Result};
+
+#[cfg(feature = "repl")]
+mod repl;
 
-use color_eyre::eyre::{eyre, Result};
+// exit codes besides 0 and the ones given to the keyword exit, as in sysexits.h.
+// clap exits with 2 on invalid command line arguments, anything else that fails
+// exits with 1
+const EXIT_LEXER_ERROR: i32 = 65;
+const EXIT_SCRIPT_UNREADABLE: i32 = 66;
+const EXIT_RUNTIME_ERROR: i32 = 70;
+
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    after_help = "Exit codes:\n  0   success\n  2   the command line arguments are invalid\n  65  the script can't be parsed\n  66  the script can't be read\n  70  the script failed while running\n  N   the script called exit N, which can be any of these as well\n  1   anything else failed"
+)]
+struct Cli {
+    /// Script to run, - reads it from stdin. Starts the REPL if there is none.
+    /// Everything after it is passed to the script as the list $argv, their
+    /// number is $argc. This includes options, so they have to come first
+    #[arg(trailing_var_arg = true, value_name = "SCRIPT [ARGS]")]
+    script: Vec<String>,
+    /// Run this code instead of a script, SCRIPT and ARGS are all passed to it
+    #[arg(short = 'e', long = "eval", value_name = "CODE")]
+    code: Option<String>,
+    /// Start the REPL, after running the script or code if one is given
+    #[arg(long)]
+    repl: bool,
+    /// Cast numbers to strings when + is given both instead of failing
+    #[arg(long)]
+    implicit_cast: bool,
+    /// Write what the script prints to this file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
 
-fn register_keyword(
-    global_state: GlobalState,
-    name: &str,
-    implementation: fn(GlobalState, Vec<Argument>) -> Result<GlobalState>,
-    number_of_arguments: u32,
-) -> Result<GlobalState> {
-    let mut new_state = global_state;
-    if new_state.keywords.contains_key(name) {
-        Err(eyre!(format!(
-            "Keyword {} already registered. Overwriting keyword registrations is not allowed.",
-            name
-        )))
+fn read_script(filename: &str) -> Result<String> {
+    if filename == "-" {
+        let mut code = String::new();
+        io::stdin().read_to_string(&mut code)?;
+        Ok(code)
     } else {
-        new_state.keywords.insert(
-            name.into(),
-            KeywordImplementation {
-                name: name.into(),
-                implementation,
-                number_of_arguments,
-            },
-        );
-        Ok(new_state)
+        Ok(fs::read_to_string(filename)?)
     }
 }
 
-#[derive(Parser, Debug)]
-#[command(version)]
-struct Cli {
-    filename: String,
+fn fail(exit_code: i32, report: &Report) -> ! {
+    eprintln!("Error: {report:?}");
+    std::process::exit(exit_code)
+}
+
+fn run_code(interpreter: &mut Interpreter, code: &str) -> Result<()> {
+    let tokens = parse(code).unwrap_or_else(|report| fail(EXIT_LEXER_ERROR, &report));
+    if let Ok(debug) = std::env::var("KFKSCRIPT_DEBUG") {
+        if debug == "1" {
+            print_tokens(tokens.clone(), &interpreter.state().output)?;
+        }
+    }
+    let result = interpreter.eval_tokens(&tokens);
+    interpreter.state().output.flush()?;
+    if let Err(report) = result {
+        fail(EXIT_RUNTIME_ERROR, &report);
+    }
+    if let Some(exit_code) = interpreter.exit_code() {
+        std::process::exit(exit_code);
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
     let args = Cli::parse();
-    let code: String = fs::read_to_string(args.filename)?;
-    let tokens = parse(&code)?;
-    let mut global_state: GlobalState = GlobalState {
-        variables: HashMap::new(),
-        keywords: HashMap::new(),
-        ret: Some(Argument::Number(0.0)),
-        nesting: vec![],
-        line_number: 0,
-        scopes: vec![],
-        subroutines: HashMap::new(),
-        subroutine_name: None,
-    };
-    global_state = register_keyword(global_state, "println", keywords::println, 1)?;
-    global_state = register_keyword(global_state, "+", keywords::add, 2)?;
-    global_state = register_keyword(global_state, "-", keywords::subtract, 2)?;
-    global_state = register_keyword(global_state, "if", keywords::if_, 1)?;
-    global_state = register_keyword(global_state, "else", keywords::else_, 0)?;
-    global_state = register_keyword(global_state, "end", keywords::end, 0)?;
-    global_state = register_keyword(global_state, "let", keywords::let_, 2)?;
-    global_state = register_keyword(global_state, "tel", keywords::tel, 1)?;
-    global_state = register_keyword(global_state, "==", keywords::eq, 2)?;
-    global_state = register_keyword(global_state, "<", keywords::less_than, 2)?;
-    global_state = register_keyword(global_state, "!", keywords::not, 1)?;
-    global_state = register_keyword(global_state, "true", keywords::true_, 0)?;
-    global_state = register_keyword(global_state, "false", keywords::false_, 0)?;
-    global_state = register_keyword(global_state, "scope::push", keywords::scope_push, 0)?;
-    global_state = register_keyword(global_state, "scope::pop", keywords::scope_pop, 0)?;
-    global_state = register_keyword(
-        global_state,
-        "scope::outer::let",
-        keywords::scope_outer_let,
-        2,
-    )?;
-    global_state = register_keyword(
-        global_state,
-        "scope::outer::tel",
-        keywords::scope_outer_tel,
-        1,
-    )?;
-    global_state = register_keyword(global_state, "return", keywords::return_, 1)?;
-    global_state = register_keyword(global_state, "subroutine", keywords::subroutine, 1)?;
-    global_state = register_keyword(global_state, "run", keywords::run, 1)?;
+    let mut interpreter = Interpreter::with_state(GlobalState {
+        mixed_types: if args.implicit_cast {
+            MixedTypes::Cast
+        } else {
+            MixedTypes::Error
+        },
+        ..GlobalState::default()
+    })?;
+    if let Some(path) = &args.output {
+        interpreter.set_output(Output::new(BufWriter::new(File::create(path)?)));
+    }
 
-    if let Ok(debug) = std::env::var("KFKSCRIPT_DEBUG") {
-        if debug == "1" {
-            print_tokens(tokens.clone());
-        }
+    let mut script = args.script.into_iter();
+    let code = args.code.or_else(|| {
+        script.next().map(|filename| {
+            read_script(&filename).unwrap_or_else(|report| fail(EXIT_SCRIPT_UNREADABLE, &report))
+        })
+    });
+    let script_args = script.collect();
+    interpreter.set_arguments(script_args);
+    if let Some(code) = &code {
+        run_code(&mut interpreter, code)?;
+    }
+    if args.repl || code.is_none() {
+        #[cfg(feature = "repl")]
+        repl::run(&mut interpreter)?;
+        #[cfg(not(feature = "repl"))]
+        Err(color_eyre::eyre::eyre!("No script given and kfkscript was built without the REPL"))?;
+    }
+    if let Some(exit_code) = interpreter.exit_code() {
+        std::process::exit(exit_code);
     }
-    let token_iter = tokens.iter().peekable();
-    interpreter::main_loop(token_iter, global_state)?;
     Ok(())
 }
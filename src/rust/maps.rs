@@ -0,0 +1,137 @@
+use color_eyre::eyre::{eyre, OptionExt};
+use color_eyre::Result;
+
+use crate::expression::{Argument, GlobalState};
+use crate::keywords::{nth_argument, number_from_usize};
+
+fn map_argument<'a>(args: &'a [Argument], index: usize, keyword: &str, line_number: u32) -> Result<&'a Vec<(Argument, Argument)>> {
+    match nth_argument(args, index, keyword, line_number)? {
+        Argument::Map(entries) => Ok(entries),
+        arg => Err(eyre!(format!("cannot use argument of type {} with keyword {keyword} in line {line_number}", arg.type_name()))),
+    }
+}
+
+// map::set and map::remove change the map stored in a variable, so they take the
+// name of that variable instead of the map itself, like list::push does
+fn map_variable(global_state: &GlobalState, name: &Argument, keyword: &str) -> Result<Vec<(Argument, Argument)>> {
+    match global_state.variables.get(name).ok_or_eyre(format!(
+        "No such variable {name} found in line {}",
+        global_state.line_number
+    ))? {
+        Argument::Map(entries) => Ok(entries.clone()),
+        arg => Err(eyre!(format!("cannot use variable {name} of type {} with keyword {keyword} in line {}", arg.type_name(), global_state.line_number))),
+    }
+}
+
+fn insert(entries: &mut Vec<(Argument, Argument)>, key: Argument, value: Argument) {
+    if let Some(entry) = entries.iter_mut().find(|(existing_key, _)| existing_key == &key) {
+        entry.1 = value;
+    } else {
+        entries.push((key, value));
+    }
+}
+
+// takes keys and values in turns, e.g. map::new@4 $name 'kfk" $size 42
+pub fn new(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    if !args.len().is_multiple_of(2) {
+        Err(eyre!(format!(
+            "map::new expects keys and values in pairs but got {} arguments in line {}",
+            args.len(),
+            global_state.line_number
+        )))?;
+    }
+    let mut new_state = global_state;
+    let mut entries = vec![];
+    let mut arg_iter = args.into_iter();
+    while let (Some(key), Some(value)) = (arg_iter.next(), arg_iter.next()) {
+        insert(&mut entries, key, value);
+    }
+    new_state.ret = Argument::Map(entries);
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn get(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let entries = map_argument(&args, 0, "map::get", new_state.line_number)?;
+    let key = nth_argument(&args, 1, "map::get", new_state.line_number)?;
+    let (_, value) = entries
+        .iter()
+        .find(|(existing_key, _)| existing_key == key)
+        .ok_or_eyre(format!(
+            "No such key {key} found in map in line {}",
+            new_state.line_number
+        ))?;
+    new_state.ret = value.clone();
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn set(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let name = nth_argument(&args, 0, "map::set", new_state.line_number)?;
+    let key = nth_argument(&args, 1, "map::set", new_state.line_number)?;
+    let value = nth_argument(&args, 2, "map::set", new_state.line_number)?;
+    let mut entries = map_variable(&new_state, name, "map::set")?;
+    insert(&mut entries, key.clone(), value.clone());
+    new_state.variables.insert(name.clone(), Argument::Map(entries.clone()));
+    new_state.ret = Argument::Map(entries);
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn has(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let entries = map_argument(&args, 0, "map::has", new_state.line_number)?;
+    let key = nth_argument(&args, 1, "map::has", new_state.line_number)?;
+    new_state.ret = Argument::Bool(entries.iter().any(|(existing_key, _)| existing_key == key));
+    Ok(new_state)
+}
+
+// returns the removed value
+#[allow(clippy::needless_pass_by_value)]
+pub fn remove(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let name = nth_argument(&args, 0, "map::remove", new_state.line_number)?;
+    let key = nth_argument(&args, 1, "map::remove", new_state.line_number)?;
+    let mut entries = map_variable(&new_state, name, "map::remove")?;
+    let index = entries
+        .iter()
+        .position(|(existing_key, _)| existing_key == key)
+        .ok_or_eyre(format!(
+            "No such key {key} found in map {name} in line {}",
+            new_state.line_number
+        ))?;
+    let (_, value) = entries.remove(index);
+    new_state.variables.insert(name.clone(), Argument::Map(entries));
+    new_state.ret = value;
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn keys(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let entries = map_argument(&args, 0, "map::keys", new_state.line_number)?;
+    new_state.ret = Argument::List(
+        entries.iter().map(|(key, _)| key.clone()).collect(),
+    );
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn values(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let entries = map_argument(&args, 0, "map::values", new_state.line_number)?;
+    new_state.ret = Argument::List(
+        entries.iter().map(|(_, value)| value.clone()).collect(),
+    );
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn len(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let entries = map_argument(&args, 0, "map::len", new_state.line_number)?;
+    new_state.ret = number_from_usize(entries.len());
+    Ok(new_state)
+}
@@ -0,0 +1,144 @@
+use color_eyre::eyre::{eyre, OptionExt};
+use color_eyre::Result;
+
+use crate::expression::{Argument, GlobalState};
+use crate::keywords::{as_float, float_arguments, numeric_arguments};
+
+#[allow(clippy::needless_pass_by_value)]
+fn apply(
+    global_state: GlobalState,
+    args: Vec<Argument>,
+    keyword: &str,
+    operation: impl Fn(f64) -> Result<f64>,
+) -> Result<GlobalState> {
+    let number = *float_arguments(&args, keyword, global_state.line_number)?
+        .first()
+        .ok_or_eyre(format!("Argument of {keyword} not found in line {}. This error should never surface, please inform the developers of Kfkscript.", global_state.line_number))?;
+    let mut new_state = global_state;
+    new_state.ret = Argument::Number(operation(number)?);
+    Ok(new_state)
+}
+
+// like apply, but integers are kept exact as long as integer_operation can
+fn apply_exact(
+    global_state: GlobalState,
+    args: Vec<Argument>,
+    keyword: &str,
+    integer_operation: fn(i64) -> Option<i64>,
+    float_operation: fn(f64) -> f64,
+) -> Result<GlobalState> {
+    let number = numeric_arguments(args, keyword, global_state.line_number)?
+        .into_iter()
+        .next()
+        .ok_or_eyre(format!("Argument of {keyword} not found in line {}. This error should never surface, please inform the developers of Kfkscript.", global_state.line_number))?;
+    let mut new_state = global_state;
+    new_state.ret = match number {
+        Argument::Integer(i) => integer_operation(i).map_or_else(
+            || Argument::Number(float_operation(as_float(&number).unwrap_or(f64::NAN))),
+            Argument::Integer,
+        ),
+        // whole already, math::abs takes care of its sign
+        #[cfg(feature = "bigint")]
+        Argument::BigInt(_) => number,
+        number => Argument::Number(float_operation(as_float(&number).unwrap_or(f64::NAN))),
+    };
+    Ok(new_state)
+}
+
+// returns the selected argument as it is, so integers stay integers
+fn select(
+    global_state: GlobalState,
+    args: Vec<Argument>,
+    keyword: &str,
+    keep_first: fn(f64, f64) -> bool,
+) -> Result<GlobalState> {
+    let result = numeric_arguments(args, keyword, global_state.line_number)?
+        .into_iter()
+        .reduce(|a, b| {
+            if keep_first(as_float(&a).unwrap_or(f64::NAN), as_float(&b).unwrap_or(f64::NAN)) {
+                a
+            } else {
+                b
+            }
+        })
+        .ok_or_eyre(format!("No arguments supplied to keyword {keyword} in line {}", global_state.line_number))?;
+    let mut new_state = global_state;
+    new_state.ret = result;
+    Ok(new_state)
+}
+
+pub fn abs(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    #[cfg(feature = "bigint")]
+    if let Some(Argument::BigInt(integer)) = args.first() {
+        let mut new_state = global_state;
+        new_state.ret = Argument::BigInt(num_traits::Signed::abs(integer));
+        return Ok(new_state);
+    }
+    apply_exact(global_state, args, "math::abs", i64::checked_abs, f64::abs)
+}
+
+pub fn floor(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    apply_exact(global_state, args, "math::floor", Some, f64::floor)
+}
+
+pub fn ceil(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    apply_exact(global_state, args, "math::ceil", Some, f64::ceil)
+}
+
+pub fn round(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    apply_exact(global_state, args, "math::round", Some, f64::round)
+}
+
+pub fn sqrt(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let line_number = global_state.line_number;
+    apply(global_state, args, "math::sqrt", |n| {
+        if n < 0.0 {
+            Err(eyre!(format!("Cannot take the square root of negative number {n} in line {line_number}")))
+        } else {
+            Ok(n.sqrt())
+        }
+    })
+}
+
+pub fn min(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    select(global_state, args, "math::min", |a, b| a <= b)
+}
+
+pub fn max(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    select(global_state, args, "math::max", |a, b| a >= b)
+}
+
+pub fn sin(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    apply(global_state, args, "math::sin", |n| Ok(n.sin()))
+}
+
+pub fn cos(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    apply(global_state, args, "math::cos", |n| Ok(n.cos()))
+}
+
+pub fn tan(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    apply(global_state, args, "math::tan", |n| Ok(n.tan()))
+}
+
+// natural logarithm
+pub fn log(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let line_number = global_state.line_number;
+    apply(global_state, args, "math::log", |n| {
+        if n <= 0.0 {
+            Err(eyre!(format!("Cannot take the logarithm of non-positive number {n} in line {line_number}")))
+        } else {
+            Ok(n.ln())
+        }
+    })
+}
+
+pub fn exp(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    apply(global_state, args, "math::exp", |n| Ok(n.exp()))
+}
+
+#[allow(clippy::needless_pass_by_value, clippy::unnecessary_wraps)]
+pub fn pi(global_state: GlobalState, _args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    new_state.ret = Argument::Number(std::f64::consts::PI);
+    Ok(new_state)
+}
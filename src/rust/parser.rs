@@ -1,3 +1,4 @@
+use crate::streams::Output;
 use crate::token;
 use crate::token::{Token, TokenType};
 
@@ -25,14 +26,66 @@ fn determine_next_token_type(current_char: char, next_char: Option<&char>) -> (T
     }
 }
 
+// turns the characters collected for a token into the token
+fn finish_token(token_type: &TokenType, prelim: String, line_number: u32) -> Result<Token> {
+    match token_type {
+        TokenType::KfkApostropheString | TokenType::KfkDollarString => {
+            Ok(Token::KfkString(token::KfkString {
+                lexem: prelim,
+                line_number,
+            }))
+        }
+        TokenType::Keyword => Ok(Token::Keyword(token::Keyword {
+            lexem: prelim,
+            line_number,
+        })),
+        // literals without a decimal point are exact integers if they fit into one,
+        // or into an arbitrary-precision one with the bigint feature
+        TokenType::Number => match prelim.parse() {
+            Ok(integer) if !prelim.contains('.') => Ok(Token::Integer(token::Integer {
+                lexem: prelim,
+                integer,
+                line_number,
+            })),
+            #[cfg(feature = "bigint")]
+            _ if prelim.parse::<num_bigint::BigInt>().is_ok() => Ok(Token::BigInt(token::BigInteger {
+                integer: prelim.parse()?,
+                lexem: prelim,
+                line_number,
+            })),
+            _ => {
+                let number = prelim.parse().map_err(|_| {
+                    eyre!(format!("invalid number in line {line_number}: {prelim}"))
+                })?;
+                Ok(Token::Number(token::Number {
+                    lexem: prelim,
+                    number,
+                    line_number,
+                }))
+            }
+        },
+        TokenType::None => Err(eyre!(format!("No token to finish in line {line_number}. This error should never surface, please inform the developers of Kfkscript."))),
+    }
+}
+
 pub fn parse(source_code: &str) -> Result<Vec<Token>> {
     let mut tokens: Vec<Token> = vec![];
     let mut line_number = 1;
     let mut prelim = String::new();
-    let mut token_type = TokenType::Keyword;
+    let mut token_type = TokenType::None;
     let mut source_code_iter = source_code.chars().peekable();
     let mut is_comment = false;
 
+    // a first line like #!/usr/bin/env kfkscript lets scripts be executed directly
+    if source_code.starts_with("#!") {
+        for current_char in source_code_iter.by_ref() {
+            if current_char == '\n' {
+                line_number += 1;
+                break;
+            }
+        }
+    }
+
     while let Some(current_char) = source_code_iter.next() {
         let next_char = source_code_iter.peek();
         let newline = current_char == '\n';
@@ -58,102 +111,177 @@ pub fn parse(source_code: &str) -> Result<Vec<Token>> {
             continue;
         }
 
-        match token_type {
-            TokenType::KfkApostropheString => {
-                if current_char == '"' {
-                    tokens.push(Token::KfkString(token::KfkString {
-                        lexem: prelim,
-                        line_number,
-                    }));
-                    prelim = String::new();
-                    token_type = TokenType::None;
-                } else {
-                    prelim = format!("{prelim}{current_char}");
-                }
-            }
-            TokenType::KfkDollarString => {
-                if current_char == ' ' || current_char == '\n' {
-                    tokens.push(Token::KfkString(token::KfkString {
-                        lexem: prelim,
-                        line_number,
-                    }));
-                    prelim = String::new();
-                    token_type = TokenType::None;
-                } else {
-                    prelim = format!("{prelim}{current_char}");
-                }
-            }
-            TokenType::Keyword => {
-                if current_char.is_whitespace() || current_char == '#' {
-                    is_comment = current_char == '#';
-                    tokens.push(Token::Keyword(token::Keyword {
-                        lexem: prelim,
-                        line_number,
-                    }));
-                    prelim = String::new();
-                    token_type = TokenType::None;
-                } else {
-                    prelim = format!("{prelim}{current_char}");
-                }
-            }
-            TokenType::Number => {
-                if current_char.is_whitespace() || current_char == '#' {
-                    is_comment = current_char == '#';
-                    let number = prelim.parse().map_err(|_| {
-                        eyre!(format!("invalid number in line {line_number}: {prelim}"))
-                    })?;
-                    tokens.push(Token::Number(token::Number {
-                        lexem: prelim,
-                        number,
-                        line_number,
-                    }));
-                    prelim = String::new();
-                    token_type = TokenType::None;
-                } else {
-                    prelim = format!("{prelim}{current_char}");
-                }
+        let token_ends = match token_type {
+            TokenType::KfkApostropheString => current_char == '"',
+            TokenType::KfkDollarString => current_char == ' ' || current_char == '\n',
+            TokenType::Keyword | TokenType::Number => {
+                is_comment = current_char == '#';
+                current_char.is_whitespace() || is_comment
             }
-            TokenType::None => {}
+            TokenType::None => false,
+        };
+        if token_ends {
+            tokens.push(finish_token(&token_type, prelim, line_number)?);
+            prelim = String::new();
+            token_type = TokenType::None;
+        } else {
+            prelim.push(current_char);
         }
         if newline {
             line_number += 1;
         }
     }
+    // the last token doesn't have to be followed by whitespace
+    match token_type {
+        TokenType::None => {}
+        TokenType::KfkApostropheString => Err(eyre!(format!(
+            "string '{prelim} not terminated by \" in line {line_number}"
+        )))?,
+        _ => tokens.push(finish_token(&token_type, prelim, line_number)?),
+    }
     Ok(tokens)
 }
 
-pub fn print_tokens(tokens: Vec<Token>) {
+pub fn print_tokens(tokens: Vec<Token>, output: &Output) -> Result<()> {
+    let mut output = output.lock()?;
     let mut current_line: u32 = 1;
     for token in tokens {
         match token {
             Token::Keyword(token) => {
                 if token.line_number > current_line {
                     for _ in current_line..token.line_number {
-                        println!();
+                        writeln!(output)?;
                     }
                     current_line = token.line_number;
                 }
-                print!("{} ", token.lexem);
+                write!(output, "{} ", token.lexem)?;
             }
             Token::KfkString(token) => {
                 if token.line_number > current_line {
                     for _ in current_line..token.line_number {
-                        println!();
+                        writeln!(output)?;
                     }
                     current_line = token.line_number;
                 }
-                print!("'{}\" ", token.lexem);
+                write!(output, "'{}\" ", token.lexem)?;
             }
             Token::Number(token) => {
                 if token.line_number > current_line {
                     for _ in current_line..token.line_number {
-                        println!();
+                        writeln!(output)?;
                     }
                     current_line = token.line_number;
                 }
-                print!("{} ", token.lexem);
+                write!(output, "{} ", token.lexem)?;
+            }
+            Token::Integer(token) => {
+                if token.line_number > current_line {
+                    for _ in current_line..token.line_number {
+                        writeln!(output)?;
+                    }
+                    current_line = token.line_number;
+                }
+                write!(output, "{} ", token.lexem)?;
+            }
+            #[cfg(feature = "bigint")]
+            Token::BigInt(token) => {
+                if token.line_number > current_line {
+                    for _ in current_line..token.line_number {
+                        writeln!(output)?;
+                    }
+                    current_line = token.line_number;
+                }
+                write!(output, "{} ", token.lexem)?;
             }
         }
     }
-    println!();
+    writeln!(output)?;
+    drop(output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lexems(tokens: &[Token]) -> Vec<(String, u32)> {
+        tokens
+            .iter()
+            .map(|token| match token {
+                Token::Keyword(keyword) => (keyword.lexem.clone(), keyword.line_number),
+                Token::KfkString(string) => (string.lexem.clone(), string.line_number),
+                Token::Number(number) => (number.lexem.clone(), number.line_number),
+                Token::Integer(integer) => (integer.lexem.clone(), integer.line_number),
+                #[cfg(feature = "bigint")]
+                Token::BigInt(integer) => (integer.lexem.clone(), integer.line_number),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shebang_is_skipped() -> Result<()> {
+        let tokens = parse("#!/usr/bin/env kfkscript\nprintln 42\n")?;
+        assert_eq!(
+            lexems(&tokens),
+            vec![("println".into(), 2), ("42".into(), 2)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn shebang_without_code() -> Result<()> {
+        assert!(parse("#!/usr/bin/env kfkscript")?.is_empty());
+        assert!(parse("#!/usr/bin/env kfkscript\n")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn shebang_with_arguments_and_strings() -> Result<()> {
+        let tokens = parse("#!/usr/bin/env -S kfkscript --implicit-cast 'x\"\nprintln 'a\"")?;
+        assert_eq!(
+            lexems(&tokens),
+            vec![("println".into(), 2), ("a".into(), 2)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn shebang_only_on_first_line() -> Result<()> {
+        let tokens = parse("println 1\n#!/usr/bin/env kfkscript\nprintln 2")?;
+        assert_eq!(
+            lexems(&tokens),
+            vec![
+                ("println".into(), 1),
+                ("1".into(), 1),
+                ("println".into(), 3),
+                ("2".into(), 3)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hash_without_bang_is_a_comment() -> Result<()> {
+        let tokens = parse("# println 1\nprintln 2")?;
+        assert_eq!(
+            lexems(&tokens),
+            vec![("println".into(), 2), ("2".into(), 2)]
+        );
+        Ok(())
+    }
+
+    // the literals are parsed exactly
+    #[allow(clippy::float_cmp)]
+    #[test]
+    fn integer_literals_that_do_not_fit_are_numbers() -> Result<()> {
+        let tokens = parse("42 4.2 1e5 99999999999999999999")?;
+        assert!(matches!(tokens[0], Token::Integer(token::Integer { integer: 42, .. })));
+        assert!(matches!(&tokens[1], Token::Number(number) if number.number == 4.2));
+        assert!(matches!(&tokens[2], Token::Number(number) if number.number == 1e5));
+        #[cfg(not(feature = "bigint"))]
+        assert!(matches!(&tokens[3], Token::Number(number) if number.number == 1e20));
+        #[cfg(feature = "bigint")]
+        assert!(matches!(&tokens[3], Token::BigInt(integer) if integer.integer.to_string() == "99999999999999999999"));
+        Ok(())
+    }
 }
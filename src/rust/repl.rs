@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use color_eyre::Result;
+use kfkscript::control_flow::is_block_start;
+use kfkscript::parser::parse;
+use kfkscript::token::Token;
+use kfkscript::{Argument, GlobalState, Interpreter};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+// completes the word under the cursor with the names of the registered keywords
+struct KeywordCompleter {
+    keywords: Vec<String>,
+}
+
+impl KeywordCompleter {
+    fn new(global_state: &GlobalState) -> Self {
+        let mut keywords: Vec<String> = global_state.keywords.keys().cloned().collect();
+        keywords.sort();
+        Self { keywords }
+    }
+}
+
+impl Completer for KeywordCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let word = line[..pos].rsplit(char::is_whitespace).next().unwrap_or_default();
+        let candidates = self
+            .keywords
+            .iter()
+            .filter(|keyword| keyword.starts_with(word))
+            .cloned()
+            .collect();
+        Ok((pos - word.len(), candidates))
+    }
+}
+
+impl Hinter for KeywordCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for KeywordCompleter {}
+
+impl Validator for KeywordCompleter {}
+
+impl Helper for KeywordCompleter {}
+
+// number of if, while, for, subroutine and keyword blocks that still lack their end.
+// Code that can't be parsed is left for eval to report
+fn open_blocks(code: &str) -> i64 {
+    parse(code).map_or(0, |tokens| {
+        tokens
+            .iter()
+            .map(|token| match token {
+                Token::Keyword(keyword) if is_block_start(&keyword.lexem) => 1,
+                Token::Keyword(keyword) if keyword.lexem == "end" => -1,
+                _ => 0,
+            })
+            .sum()
+    })
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".kfkscript_history"))
+}
+
+// reads code until every block in it is ended, evaluates it and prints what it
+// returned. Ctrl-C discards the code read so far, Ctrl-D and exit quit
+pub fn run(interpreter: &mut Interpreter) -> Result<()> {
+    let mut editor: Editor<KeywordCompleter, FileHistory> = Editor::new()?;
+    editor.set_helper(Some(KeywordCompleter::new(interpreter.state())));
+    let history = history_path();
+    if let Some(path) = &history {
+        // there is no history before the first session
+        editor.load_history(path).ok();
+    }
+    let mut code = String::new();
+    loop {
+        let prompt = if code.is_empty() { "> " } else { "... " };
+        match editor.readline(prompt) {
+            Ok(line) => {
+                editor.add_history_entry(line.as_str())?;
+                code.push_str(&line);
+                code.push('\n');
+                if open_blocks(&code) > 0 {
+                    continue;
+                }
+                match interpreter.eval(&code) {
+                    Ok(Argument::Nil) => {}
+                    Ok(ret) => writeln!(interpreter.state().output.lock()?, "{ret}")?,
+                    Err(error) => eprintln!("Error: {error}"),
+                }
+                interpreter.state().output.flush()?;
+                if interpreter.exit_code().is_some() {
+                    break;
+                }
+                code.clear();
+                editor.set_helper(Some(KeywordCompleter::new(interpreter.state())));
+            }
+            Err(ReadlineError::Interrupted) => code.clear(),
+            Err(ReadlineError::Eof) => break,
+            Err(error) => Err(error)?,
+        }
+    }
+    if let Some(path) = &history {
+        editor.save_history(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use std::io::{self, BufRead, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+
+// where println and the debug helpers write to. Clones of the state share it
+#[derive(Clone)]
+pub struct Output(Arc<Mutex<dyn Write + Send>>);
+
+impl Output {
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(writer)))
+    }
+
+    #[must_use]
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+
+    pub fn lock(&self) -> Result<MutexGuard<'_, dyn Write + Send + 'static>> {
+        self.0
+            .lock()
+            .map_err(|_| eyre!("Output can't be written to because writing to it failed before"))
+    }
+
+    pub fn flush(&self) -> Result<()> {
+        self.lock()?.flush()?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Output").finish_non_exhaustive()
+    }
+}
+
+// where readln reads from. Clones of the state share it
+#[derive(Clone)]
+pub struct Input(Arc<Mutex<dyn BufRead + Send>>);
+
+impl Input {
+    pub fn new(reader: impl BufRead + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(reader)))
+    }
+
+    #[must_use]
+    pub fn stdin() -> Self {
+        Self::new(io::BufReader::new(io::stdin()))
+    }
+
+    // the next line without its line break, None once there is nothing left
+    pub fn read_line(&self) -> Result<Option<String>> {
+        let mut line = String::new();
+        let bytes_read = self
+            .0
+            .lock()
+            .map_err(|_| eyre!("Input can't be read from because reading from it failed before"))?
+            .read_line(&mut line)?;
+        if bytes_read == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+}
+
+impl std::fmt::Debug for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Input").finish_non_exhaustive()
+    }
+}
+
+// an in-memory writer for Output::new that can still be read after being handed
+// over, e.g. to capture what a script prints
+#[derive(Clone, Debug, Default)]
+pub struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Buffer {
+    #[must_use]
+    pub fn contents(&self) -> String {
+        self.0
+            .lock()
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default()
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .map_err(|_| io::Error::other("buffer poisoned"))?
+            .write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
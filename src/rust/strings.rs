@@ -0,0 +1,190 @@
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+
+use crate::expression::{Argument, GlobalState};
+use crate::keywords::{index_argument, nth_argument, number_from_usize, string_argument};
+
+#[must_use]
+pub fn stringify(arg: &Argument) -> String {
+    match arg {
+        Argument::KfkString(string) => string.clone(),
+        Argument::Number(number) => number.to_string(),
+        Argument::Integer(integer) => integer.to_string(),
+        #[cfg(feature = "bigint")]
+        Argument::BigInt(integer) => integer.to_string(),
+        Argument::Bool(_)
+        | Argument::Nil
+        | Argument::List(_)
+        | Argument::Map(_)
+        | Argument::Handle(_) => arg.to_string(),
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn cast(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let arg = nth_argument(&args, 0, "str::cast", new_state.line_number)?;
+    new_state.ret = Argument::KfkString(stringify(arg));
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn len(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::len", new_state.line_number)?;
+    new_state.ret = number_from_usize(string.chars().count());
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn concat(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let concatenated = (0..args.len())
+        .map(|index| string_argument(&args, index, "str::concat", new_state.line_number))
+        .collect::<Result<String>>()?;
+    new_state.ret = Argument::KfkString(concatenated);
+    Ok(new_state)
+}
+
+// characters from start up to but not including end
+#[allow(clippy::needless_pass_by_value)]
+pub fn slice(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::slice", new_state.line_number)?;
+    let start = index_argument(&args, 1, "str::slice", new_state.line_number)?;
+    let end = index_argument(&args, 2, "str::slice", new_state.line_number)?;
+    let length = string.chars().count();
+    if start > end || end > length {
+        Err(eyre!(format!(
+            "cannot slice characters {start} to {end} of a string of length {length} in line {}",
+            new_state.line_number
+        )))?;
+    }
+    new_state.ret = Argument::KfkString(
+        string.chars().skip(start).take(end - start).collect(),
+    );
+    Ok(new_state)
+}
+
+// character index of the first occurrence or -1 if there is none
+#[allow(clippy::needless_pass_by_value)]
+pub fn index_of(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::index-of", new_state.line_number)?;
+    let needle = string_argument(&args, 1, "str::index-of", new_state.line_number)?;
+    new_state.ret = string.find(&needle).map_or_else(
+        || Argument::Integer(-1),
+        |byte_index| number_from_usize(string[..byte_index].chars().count()),
+    );
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn split(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::split", new_state.line_number)?;
+    let separator = string_argument(&args, 1, "str::split", new_state.line_number)?;
+    if separator.is_empty() {
+        Err(eyre!(format!(
+            "cannot split by an empty string in line {}",
+            new_state.line_number
+        )))?;
+    }
+    new_state.ret = Argument::List(
+        string
+            .split(&separator)
+            .map(|part| Argument::KfkString(part.into()))
+            .collect(),
+    );
+    Ok(new_state)
+}
+
+// items that aren't strings are joined as str::cast would turn them into strings
+#[allow(clippy::needless_pass_by_value)]
+pub fn join(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let items = match nth_argument(&args, 0, "str::join", new_state.line_number)? {
+        Argument::List(items) => items,
+        arg => Err(eyre!(format!("cannot use argument of type {} with keyword str::join in line {}", arg.type_name(), new_state.line_number)))?,
+    };
+    let separator = string_argument(&args, 1, "str::join", new_state.line_number)?;
+    new_state.ret = Argument::KfkString(
+        items.iter().map(stringify).collect::<Vec<String>>().join(&separator),
+    );
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn trim(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::trim", new_state.line_number)?;
+    new_state.ret = Argument::KfkString(string.trim().into());
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn upper(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::upper", new_state.line_number)?;
+    new_state.ret = Argument::KfkString(string.to_uppercase());
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn lower(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::lower", new_state.line_number)?;
+    new_state.ret = Argument::KfkString(string.to_lowercase());
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn replace(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::replace", new_state.line_number)?;
+    let from = string_argument(&args, 1, "str::replace", new_state.line_number)?;
+    let to = string_argument(&args, 2, "str::replace", new_state.line_number)?;
+    if from.is_empty() {
+        Err(eyre!(format!(
+            "cannot replace an empty string in line {}",
+            new_state.line_number
+        )))?;
+    }
+    new_state.ret = Argument::KfkString(string.replace(&from, &to));
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn starts_with(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::starts-with", new_state.line_number)?;
+    let prefix = string_argument(&args, 1, "str::starts-with", new_state.line_number)?;
+    new_state.ret = Argument::Bool(string.starts_with(&prefix));
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn ends_with(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::ends-with", new_state.line_number)?;
+    let suffix = string_argument(&args, 1, "str::ends-with", new_state.line_number)?;
+    new_state.ret = Argument::Bool(string.ends_with(&suffix));
+    Ok(new_state)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn repeat(global_state: GlobalState, args: Vec<Argument>) -> Result<GlobalState> {
+    let mut new_state = global_state;
+    let string = string_argument(&args, 0, "str::repeat", new_state.line_number)?;
+    let times = index_argument(&args, 1, "str::repeat", new_state.line_number)?;
+    // String::repeat panics instead of failing if the result can't be allocated
+    if string.len().checked_mul(times).is_none_or(|length| isize::try_from(length).is_err()) {
+        Err(eyre!(format!(
+            "string of length {} repeated {times} times is too long in line {}",
+            string.chars().count(),
+            new_state.line_number
+        )))?;
+    }
+    new_state.ret = Argument::KfkString(string.repeat(times));
+    Ok(new_state)
+}
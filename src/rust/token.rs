@@ -28,9 +28,29 @@ pub struct Number {
     pub line_number: u32,
 }
 
+#[allow(clippy::struct_field_names)]
+#[derive(Debug, Clone)]
+pub struct Integer {
+    pub lexem: String,
+    pub integer: i64,
+    pub line_number: u32,
+}
+
+#[cfg(feature = "bigint")]
+#[allow(clippy::struct_field_names)]
+#[derive(Debug, Clone)]
+pub struct BigInteger {
+    pub lexem: String,
+    pub integer: num_bigint::BigInt,
+    pub line_number: u32,
+}
+
 #[derive(Debug, Clone)]
 pub enum Token {
     Keyword(Keyword),
     KfkString(KfkString),
     Number(Number),
+    Integer(Integer),
+    #[cfg(feature = "bigint")]
+    BigInt(BigInteger),
 }
@@ -0,0 +1,87 @@
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+fn error(interpreter: &mut Interpreter, code: &str) -> Option<String> {
+    interpreter.eval(code).err().map(|error| error.to_string())
+}
+
+#[test]
+fn multiply() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("* 6 7")?, Argument::Integer(42));
+    assert_eq!(interpreter.eval("* 2 1.5")?, Argument::Number(3.0));
+    Ok(())
+}
+
+#[test]
+fn divide() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("/ 8 2")?, Argument::Integer(4));
+    // integers that don't divide evenly give a float
+    assert_eq!(interpreter.eval("/ 7 2")?, Argument::Number(3.5));
+    assert_eq!(interpreter.eval("/ 1 0.5")?, Argument::Number(2.0));
+    Ok(())
+}
+
+#[test]
+fn modulo_and_power() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("% 7 3")?, Argument::Integer(1));
+    assert_eq!(interpreter.eval("% -7 3")?, Argument::Integer(-1));
+    assert_eq!(interpreter.eval("% 7.5 2")?, Argument::Number(1.5));
+    assert_eq!(interpreter.eval("** 2 10")?, Argument::Integer(1024));
+    assert_eq!(interpreter.eval("** 2 -1")?, Argument::Number(0.5));
+    assert_eq!(interpreter.eval("** 4 0.5")?, Argument::Number(2.0));
+    Ok(())
+}
+
+#[test]
+fn division_by_zero_is_an_error() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(error(&mut interpreter, "/ 1 0").as_deref(), Some("Division by zero in line 1"));
+    assert_eq!(error(&mut interpreter, "/ 1 0.0").as_deref(), Some("Division by zero in line 1"));
+    assert_eq!(error(&mut interpreter, "/@3 1 2 0").as_deref(), Some("Division by zero in line 1"));
+    assert_eq!(error(&mut interpreter, "% 1 0").as_deref(), Some("Modulo by zero in line 1"));
+    Ok(())
+}
+
+#[test]
+fn tiny_divisors_are_not_zero() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert!(matches!(
+        interpreter.eval("/ 1 0.000000001")?,
+        Argument::Number(quotient) if (quotient - 1e9).abs() < 1e-3
+    ));
+    assert!(interpreter.eval("% 1 0.000000001").is_ok());
+    Ok(())
+}
+
+#[test]
+fn arithmetic_rejects_other_types() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(
+        error(&mut interpreter, "* 2 true").as_deref(),
+        Some("cannot use argument of type Bool with keyword * in line 1")
+    );
+    Ok(())
+}
+
+#[test]
+fn math_keywords() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("math::abs -3")?, Argument::Integer(3));
+    assert_eq!(interpreter.eval("math::floor 2.5")?, Argument::Integer(2));
+    assert_eq!(interpreter.eval("math::ceil 2.5")?, Argument::Integer(3));
+    assert_eq!(interpreter.eval("math::round -2.5")?, Argument::Integer(-3));
+    assert_eq!(interpreter.eval("math::sqrt 16")?, Argument::Number(4.0));
+    assert_eq!(interpreter.eval("math::min@3 3 1.5 2")?, Argument::Number(1.5));
+    assert_eq!(interpreter.eval("math::max@3 3 1.5 2")?, Argument::Integer(3));
+    assert_eq!(interpreter.eval("math::exp 0")?, Argument::Number(1.0));
+    assert_eq!(interpreter.eval("math::cos 0")?, Argument::Number(1.0));
+    assert!(matches!(
+        interpreter.eval("math::pi")?,
+        Argument::Number(pi) if (pi - std::f64::consts::PI).abs() < 1e-12
+    ));
+    assert!(interpreter.eval("math::sqrt $four").is_err());
+    Ok(())
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "bigint")]
+
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+#[test]
+fn results_that_fit_become_integers() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let ret = interpreter.eval("- + 9223372036854775807 1 9223372036854775807")?;
+    assert!(matches!(ret, Argument::Integer(1)));
+    Ok(())
+}
+
+#[test]
+fn bigints_that_fit_are_accepted_as_integers() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert!(matches!(interpreter.eval("bit::and bigint 5 3")?, Argument::Integer(1)));
+    let ret = interpreter.eval("list::get list::new@3 'a\" 'b\" 'c\" bigint 1")?;
+    assert_eq!(ret, Argument::KfkString("b".into()));
+    Ok(())
+}
+
+#[test]
+fn large_literals_are_bigints() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let ret = interpreter.eval("+ 99999999999999999999 1")?;
+    assert!(matches!(&ret, Argument::BigInt(_)));
+    assert_eq!(ret.to_string(), "100000000000000000000");
+    Ok(())
+}
+
+#[test]
+fn integer_division_overflow_is_a_bigint() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let ret = interpreter.eval("int::div -9223372036854775808 -1")?;
+    assert_eq!(ret.to_string(), "9223372036854775808");
+    Ok(())
+}
+
+#[test]
+fn equal_bigints_and_numbers_hash_equally() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let integer = interpreter.eval("** bigint 2 70")?;
+    let number = interpreter.eval("** 2.0 70")?;
+    assert_eq!(integer, number);
+    let set: std::collections::HashSet<Argument> = [integer, number].into_iter().collect();
+    assert_eq!(set.len(), 1);
+    assert_eq!(interpreter.eval("== + ** bigint 2 70 1 ** 2.0 70")?, Argument::Bool(false));
+    Ok(())
+}
+
+#[test]
+fn powers_that_are_too_large_are_an_error() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let error = interpreter.eval("** 7 300000000").err().map(|error| error.to_string());
+    assert_eq!(
+        error.as_deref(),
+        Some("result of ** 7 300000000 too large, it would have more than 1048576 bits in line 1")
+    );
+    assert!(interpreter.eval("** 2 99999999999999999999").is_err());
+    assert!(matches!(interpreter.eval("** -1 300000001")?, Argument::Integer(-1)));
+    assert!(matches!(interpreter.eval("** 2 1000000")?, Argument::BigInt(_)));
+    Ok(())
+}
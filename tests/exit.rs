@@ -0,0 +1,33 @@
+use std::process::Command;
+
+const BYE: &str = "keyword $bye 0\nexit 4\nend\n";
+
+fn run(code: &str) -> std::io::Result<std::process::Output> {
+    Command::new(env!("CARGO_BIN_EXE_kfkscript"))
+        .args(["-e", code])
+        .output()
+}
+
+#[test]
+fn exit_in_an_argument_stops_the_keyword() -> std::io::Result<()> {
+    let output = run(&format!("{BYE}println str::cast bye\nprintln 1"))?;
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    Ok(())
+}
+
+#[test]
+fn exit_in_an_argument_is_not_a_runtime_error() -> std::io::Result<()> {
+    let output = run(&format!("{BYE}println + bye 1"))?;
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    Ok(())
+}
+
+#[test]
+fn exit_in_a_subroutine_run_as_an_argument() -> std::io::Result<()> {
+    let output = run("subroutine $s\nexit 3\nend\nprintln str::cast run $s")?;
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::any::type_name;
+
+use color_eyre::Result;
+use kfkscript::keywords::handle_argument;
+use kfkscript::{Argument, Handle, Interpreter};
+
+struct Connection {
+    name: String,
+}
+
+fn connection(name: &str) -> Argument {
+    Argument::Handle(Handle::new(Connection { name: name.into() }))
+}
+
+fn interpreter_with(handles: &[(&str, Argument)]) -> Result<Interpreter> {
+    let mut interpreter = Interpreter::new()?;
+    for (name, handle) in handles {
+        interpreter
+            .state_mut()
+            .variables
+            .insert(Argument::KfkString((*name).into()), handle.clone());
+    }
+    interpreter.register_keyword(
+        "connection::name",
+        |global_state, args| {
+            let connection = handle_argument::<Connection>(&args, 0, "connection::name", global_state.line_number)?;
+            let mut new_state = global_state;
+            new_state.ret = Argument::KfkString(connection.name.clone());
+            Ok(new_state)
+        },
+        1,
+    )?;
+    Ok(interpreter)
+}
+
+#[test]
+fn handle_reaches_a_native_keyword_through_a_variable() -> Result<()> {
+    let mut interpreter = interpreter_with(&[("db", connection("primary"))])?;
+    interpreter.eval("let $copy tel $db")?;
+    assert_eq!(interpreter.eval("connection::name tel $copy")?, Argument::KfkString("primary".into()));
+    Ok(())
+}
+
+#[test]
+fn downcast() {
+    let handle = Handle::new(Connection { name: "primary".into() });
+    assert_eq!(handle.downcast_ref::<Connection>().map(|connection| connection.name.as_str()), Some("primary"));
+    assert!(handle.downcast::<Connection>().is_some());
+    assert!(handle.downcast_ref::<String>().is_none());
+    assert!(handle.downcast::<String>().is_none());
+}
+
+#[test]
+fn handle_of_the_wrong_type_is_an_error() -> Result<()> {
+    let mut interpreter = interpreter_with(&[("number", Argument::Handle(Handle::new(42_u32)))])?;
+    let error = interpreter
+        .eval("connection::name tel $number")
+        .err()
+        .map(|error| error.to_string());
+    assert_eq!(
+        error,
+        Some(format!(
+            "cannot use handle of type u32 with keyword connection::name in line 1, expected {}",
+            type_name::<Connection>()
+        ))
+    );
+    let error = interpreter.eval("connection::name $db").err().map(|error| error.to_string());
+    assert_eq!(
+        error.as_deref(),
+        Some("cannot use argument of type String with keyword connection::name in line 1")
+    );
+    Ok(())
+}
+
+#[test]
+fn display() -> Result<()> {
+    let mut interpreter = interpreter_with(&[("db", connection("primary"))])?;
+    let expected = format!("<handle {}>", type_name::<Connection>());
+    assert_eq!(connection("primary").to_string(), expected);
+    assert_eq!(interpreter.eval("str::cast tel $db")?, Argument::KfkString(expected));
+    Ok(())
+}
+
+#[test]
+fn equality_is_identity() -> Result<()> {
+    let db = connection("primary");
+    let mut interpreter = interpreter_with(&[("db", db.clone()), ("other", connection("primary"))])?;
+    assert_eq!(db, db.clone());
+    assert_ne!(db, connection("primary"));
+    assert_eq!(interpreter.eval("== tel $db tel $db")?, Argument::Bool(true));
+    assert_eq!(interpreter.eval("== tel $db tel $other")?, Argument::Bool(false));
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+#[test]
+fn integer_division_rounds_towards_zero() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert!(matches!(interpreter.eval("int::div@3 100 -3 2")?, Argument::Integer(-16)));
+    Ok(())
+}
+
+#[test]
+fn remainder_of_the_minimum_by_minus_one() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert!(matches!(interpreter.eval("% -9223372036854775808 -1")?, Argument::Integer(0)));
+    Ok(())
+}
+
+#[cfg(not(feature = "bigint"))]
+#[test]
+fn integer_division_overflow_is_an_error() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let error = interpreter
+        .eval("int::div -9223372036854775808 -1")
+        .err()
+        .map(|error| error.to_string());
+    assert_eq!(error.as_deref(), Some("Integer overflow in keyword int::div in line 1"));
+    Ok(())
+}
+
+#[test]
+fn integers_equal_only_exactly_equal_numbers() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("== 2 2.0")?, Argument::Bool(true));
+    assert_eq!(interpreter.eval("== 2 2.000000000001")?, Argument::Bool(false));
+    assert_eq!(interpreter.eval("== 9007199254740992 9007199254740992.0")?, Argument::Bool(true));
+    assert_eq!(interpreter.eval("== 9007199254740993 9007199254740992.0")?, Argument::Bool(false));
+    assert_eq!(
+        interpreter.eval("map::len map::new@4 9007199254740993 $a 9007199254740992.0 $b")?,
+        Argument::Integer(2)
+    );
+    Ok(())
+}
+
+#[test]
+fn equal_integers_and_numbers_hash_equally() {
+    let values = [
+        Argument::Integer(1 << 60),
+        Argument::Number(1_152_921_504_606_846_976.0),
+        Argument::Integer(0),
+        Argument::Number(-0.0),
+        Argument::Integer(9_007_199_254_740_993),
+        Argument::Number(9_007_199_254_740_992.0),
+    ];
+    let set: std::collections::HashSet<Argument> = values.into_iter().collect();
+    assert_eq!(set.len(), 4);
+}
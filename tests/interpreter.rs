@@ -0,0 +1,166 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use color_eyre::Result;
+use kfkscript::{parser, Argument, Buffer, Interpreter, Output};
+
+fn variable(interpreter: &Interpreter, name: &str) -> Option<Argument> {
+    interpreter
+        .state()
+        .variables
+        .get(&Argument::KfkString(name.into()))
+        .cloned()
+}
+
+#[test]
+fn eval_returns_the_last_value() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("+ 1 2")?, Argument::Integer(3));
+    Ok(())
+}
+
+#[test]
+fn eval_keeps_the_state_between_calls() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $x 40")?;
+    assert_eq!(interpreter.eval("+ tel $x 2")?, Argument::Integer(42));
+    Ok(())
+}
+
+#[test]
+fn eval_tokens_runs_parsed_code() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let tokens = parser::parse("let $x * 6 7")?;
+    interpreter.eval_tokens(&tokens)?;
+    interpreter.eval_tokens(&tokens)?;
+    assert_eq!(variable(&interpreter, "x"), Some(Argument::Integer(42)));
+    Ok(())
+}
+
+#[test]
+fn unterminated_block_is_an_error() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let error = interpreter.eval("if true").err().map(|error| error.to_string());
+    assert_eq!(
+        error.as_deref(),
+        Some("Block not terminated by end at the end of the code")
+    );
+    Ok(())
+}
+
+#[test]
+fn failed_eval_keeps_the_previous_state() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $x 1")?;
+    assert!(interpreter.eval("let $x 2\nlet $y 3\nif true").is_err());
+    assert!(interpreter.eval("let $x 2\ntel $missing").is_err());
+    assert_eq!(variable(&interpreter, "x"), Some(Argument::Integer(1)));
+    assert_eq!(variable(&interpreter, "y"), None);
+    // the next eval doesn't continue the unterminated block
+    assert_eq!(interpreter.eval("+ tel $x 1")?, Argument::Integer(2));
+    Ok(())
+}
+
+#[test]
+fn set_arguments_defines_argv_and_argc() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.set_arguments(vec!["a".into(), "b".into()]);
+    assert_eq!(variable(&interpreter, "argc"), Some(Argument::Integer(2)));
+    assert_eq!(interpreter.eval("list::get tel $argv 1")?, Argument::KfkString("b".into()));
+    Ok(())
+}
+
+#[test]
+fn blocks_leave_no_value_behind() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("while false\nend")?, Argument::Nil);
+    assert_eq!(interpreter.eval("if true\nend")?, Argument::Nil);
+    assert_eq!(interpreter.eval("if false\nelse\n+ 1 2\nend")?, Argument::Nil);
+    Ok(())
+}
+
+#[test]
+fn return_in_an_argument_stops_the_keyword() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("+ return 3 'x\"\nlet $x 1")?, Argument::Integer(3));
+    assert_eq!(variable(&interpreter, "x"), None);
+    Ok(())
+}
+
+#[test]
+fn registered_closure_captures_host_state() -> Result<()> {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let mut interpreter = Interpreter::new()?;
+    let captured = Arc::clone(&counter);
+    interpreter.register_keyword(
+        "count",
+        move |global_state, args| {
+            let mut new_state = global_state;
+            let step = match args.first() {
+                Some(Argument::Integer(step)) => usize::try_from(*step)?,
+                _ => 1,
+            };
+            let count = captured.fetch_add(step, Ordering::SeqCst) + step;
+            new_state.ret = Argument::Integer(i64::try_from(count)?);
+            Ok(new_state)
+        },
+        1,
+    )?;
+    interpreter.eval("count 1\ncount 2")?;
+    assert_eq!(interpreter.eval("for $x list::new@2 1 1\n  count tel $x\nend\ncount 10")?, Argument::Integer(15));
+    assert_eq!(counter.load(Ordering::SeqCst), 15);
+    Ok(())
+}
+
+#[test]
+fn registering_a_built_in_name_is_rejected() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let error = interpreter
+        .register_keyword("println", |global_state, _| Ok(global_state), 1)
+        .err()
+        .map(|error| error.to_string());
+    assert_eq!(
+        error.as_deref(),
+        Some("Keyword println already registered. Overwriting keyword registrations is not allowed.")
+    );
+    // the built-in is still the one that is called
+    let buffer = Buffer::default();
+    interpreter.set_output(Output::new(buffer.clone()));
+    interpreter.eval("println 1")?;
+    assert_eq!(buffer.contents(), "1\n");
+    Ok(())
+}
+
+#[test]
+fn call_binds_the_arguments_and_returns_the_result() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("subroutine $greet\n  return + tel $args@1 str::cast tel $args@2\nend")?;
+    let ret = interpreter.call(
+        "greet",
+        vec![Argument::KfkString("answer ".into()), Argument::Integer(42)],
+    )?;
+    assert_eq!(ret, Argument::KfkString("answer 42".into()));
+    // the arguments are gone again afterwards
+    assert_eq!(variable(&interpreter, "args@1"), None);
+    Ok(())
+}
+
+#[test]
+fn called_subroutine_runs_in_its_own_scope() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval(
+        "let $calls 0\nsubroutine $count\n  scope::outer::let $calls + scope::outer::tel $calls 1\nend",
+    )?;
+    interpreter.call("count", vec![])?;
+    interpreter.call("count", vec![])?;
+    assert_eq!(variable(&interpreter, "calls"), Some(Argument::Integer(2)));
+    Ok(())
+}
+
+#[test]
+fn calling_a_missing_subroutine_is_an_error() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let error = interpreter.call("missing", vec![]).err().map(|error| error.to_string());
+    assert_eq!(error.as_deref(), Some("Subroutine 'missing\" not found"));
+    Ok(())
+}
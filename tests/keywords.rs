@@ -0,0 +1,78 @@
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+fn error(interpreter: &mut Interpreter, code: &str) -> Option<String> {
+    interpreter.eval(code).err().map(|error| error.to_string())
+}
+
+#[test]
+fn arguments_are_bound_to_args() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("keyword $pair 2\nreturn list::new@2 tel $args@1 tel $args@2\nend")?;
+    let ret = interpreter.eval("pair 'a\" 2")?;
+    assert_eq!(ret, Argument::List(vec![Argument::KfkString("a".into()), Argument::Integer(2)]));
+    Ok(())
+}
+
+#[test]
+fn body_runs_in_a_fresh_scope() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $x 1\nkeyword $f 1\nlet $x tel $args@1\nlet $local 2\nreturn tel $x\nend")?;
+    assert_eq!(interpreter.eval("f 3")?, Argument::Integer(3));
+    assert_eq!(interpreter.eval("tel $x")?, Argument::Integer(1));
+    assert!(interpreter.eval("tel $local").is_err());
+    assert!(interpreter.eval("tel $args@1").is_err());
+    Ok(())
+}
+
+#[test]
+fn recursive_keyword_with_nested_if() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval(
+        "keyword $fact 1
+if < tel $args@1 2
+  return 1
+end
+return * tel $args@1 fact - tel $args@1 1
+end",
+    )?;
+    assert_eq!(interpreter.eval("fact 10")?, Argument::Integer(3_628_800));
+    assert!(interpreter.state().nesting.is_empty());
+    Ok(())
+}
+
+#[test]
+fn definition_in_a_loop_body_redefines_the_keyword() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval(
+        "let $sum 0
+for $i list::new@3 1 2 3
+  keyword $double 1
+  return * 2 tel $args@1
+  end
+  let $sum + tel $sum double tel $i
+end",
+    )?;
+    assert_eq!(interpreter.eval("tel $sum")?, Argument::Integer(12));
+    Ok(())
+}
+
+#[test]
+fn built_in_keywords_cannot_be_redefined() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(
+        error(&mut interpreter, "keyword $println 0\nend").as_deref(),
+        Some("Keyword println already registered in line 1. Overwriting keyword registrations is not allowed.")
+    );
+    Ok(())
+}
+
+#[test]
+fn nested_definition_is_an_error() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(
+        error(&mut interpreter, "keyword keyword $inner 0 0\nend").as_deref(),
+        Some("Nested keyword definition not allowed in line 1")
+    );
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+fn error(interpreter: &mut Interpreter, code: &str) -> Option<String> {
+    interpreter.eval(code).err().map(|error| error.to_string())
+}
+
+fn integers(integers: &[i64]) -> Argument {
+    Argument::List(integers.iter().copied().map(Argument::Integer).collect())
+}
+
+#[test]
+fn new_get_and_len() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $l list::new@3 10 20 30")?;
+    assert_eq!(interpreter.eval("tel $l")?, integers(&[10, 20, 30]));
+    assert_eq!(interpreter.eval("list::get tel $l 1")?, Argument::Integer(20));
+    assert_eq!(interpreter.eval("list::len tel $l")?, Argument::Integer(3));
+    assert_eq!(interpreter.eval("list::len list::new")?, Argument::Integer(0));
+    Ok(())
+}
+
+#[test]
+fn push_pop_and_set_change_the_variable() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $l list::new@2 1 2")?;
+    assert_eq!(interpreter.eval("list::push $l 3")?, integers(&[1, 2, 3]));
+    assert_eq!(interpreter.eval("list::set $l 0 5")?, integers(&[5, 2, 3]));
+    assert_eq!(interpreter.eval("list::pop $l")?, Argument::Integer(3));
+    assert_eq!(interpreter.eval("tel $l")?, integers(&[5, 2]));
+    Ok(())
+}
+
+#[test]
+fn slice() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $l list::new@4 1 2 3 4")?;
+    assert_eq!(interpreter.eval("list::slice tel $l 1 3")?, integers(&[2, 3]));
+    assert_eq!(interpreter.eval("list::slice tel $l 4 4")?, integers(&[]));
+    Ok(())
+}
+
+#[test]
+fn out_of_range() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $l list::new@2 1 2\nlet $empty list::new")?;
+    assert_eq!(
+        error(&mut interpreter, "list::get tel $l 2").as_deref(),
+        Some("index 2 out of range for list of length 2 in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "list::set $l 5 0").as_deref(),
+        Some("index 5 out of range for list of length 2 in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "list::slice tel $l 1 3").as_deref(),
+        Some("cannot slice items 1 to 3 of a list of length 2 in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "list::slice tel $l 2 1").as_deref(),
+        Some("cannot slice items 2 to 1 of a list of length 2 in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "list::pop $empty").as_deref(),
+        Some("cannot pop from empty list 'empty\" in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "list::get tel $l -1").as_deref(),
+        Some("expected a non-negative whole number for keyword list::get, got -1 in line 1")
+    );
+    Ok(())
+}
+
+#[test]
+fn display() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let list = interpreter.eval("list::new@4 1 $a list::new@1 true list::new")?;
+    assert_eq!(list.to_string(), "[1, 'a\", [true], []]");
+    assert_eq!(
+        interpreter.eval("str::cast list::new@2 1.5 nil")?,
+        Argument::KfkString("[1.5, nil]".into())
+    );
+    Ok(())
+}
+
+#[test]
+fn equality_and_hashing() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("== list::new@2 1 $a list::new@2 1 $a")?, Argument::Bool(true));
+    assert_eq!(interpreter.eval("== list::new@2 1 2 list::new@2 2 1")?, Argument::Bool(false));
+    assert_eq!(interpreter.eval("== list::new@1 1 list::new@2 1 1")?, Argument::Bool(false));
+    let first = interpreter.eval("list::new@2 1 list::new@1 $a")?;
+    let second = interpreter.eval("list::new@2 1 list::new@1 $a")?;
+    let reversed = interpreter.eval("list::new@2 list::new@1 $a 1")?;
+    let set: HashSet<Argument> = [first, second, reversed].into_iter().collect();
+    assert_eq!(set.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn for_iterates_over_the_items() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $sum 0\nfor $x list::new@3 1 2 3\n  let $sum + tel $sum tel $x\nend")?;
+    assert_eq!(interpreter.eval("tel $sum")?, Argument::Integer(6));
+    interpreter.eval("for $x list::new\n  let $sum 0\nend")?;
+    assert_eq!(interpreter.eval("tel $sum")?, Argument::Integer(6));
+    assert_eq!(
+        error(&mut interpreter, "for $x 5\nend").as_deref(),
+        Some("cannot iterate over argument of type Integer with keyword for in line 1")
+    );
+    Ok(())
+}
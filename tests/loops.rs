@@ -0,0 +1,208 @@
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+fn variable(interpreter: &Interpreter, name: &str) -> Option<Argument> {
+    interpreter
+        .state()
+        .variables
+        .get(&Argument::KfkString(name.into()))
+        .cloned()
+}
+
+#[test]
+fn while_evaluates_its_condition_on_each_pass() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $i 0\nwhile < tel $i 3\n  let $i + tel $i 1\nend")?;
+    assert_eq!(variable(&interpreter, "i"), Some(Argument::Integer(3)));
+    Ok(())
+}
+
+#[test]
+fn false_condition_skips_the_body() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("while false\n  let $x 1\nend\nlet $after 1")?;
+    assert_eq!(variable(&interpreter, "x"), None);
+    assert_eq!(variable(&interpreter, "after"), Some(Argument::Integer(1)));
+    Ok(())
+}
+
+#[test]
+fn while_in_a_skipped_if_is_skipped() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("if false\n  while true\n    let $x 1\n  end\nelse\n  let $y 1\nend")?;
+    assert_eq!(variable(&interpreter, "x"), None);
+    assert_eq!(variable(&interpreter, "y"), Some(Argument::Integer(1)));
+    assert!(interpreter.state().nesting.is_empty());
+    Ok(())
+}
+
+#[test]
+fn while_in_a_keyword_body_has_its_own_loop() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval(
+        "keyword $count 1
+  let $n 0
+  while < tel $n tel $args@1
+    let $n + tel $n 1
+  end
+  return tel $n
+end
+let $i 0
+let $total 0
+while < tel $i 4
+  let $total + tel $total count tel $i
+  let $i + tel $i 1
+end",
+    )?;
+    assert_eq!(variable(&interpreter, "total"), Some(Argument::Integer(6)));
+    assert_eq!(variable(&interpreter, "i"), Some(Argument::Integer(4)));
+    Ok(())
+}
+
+#[test]
+fn while_in_a_subroutine_has_its_own_loop() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval(
+        "let $calls 0
+subroutine $s
+  let $j 0
+  while < tel $j 2
+    let $calls + tel $calls 1
+    let $j + tel $j 1
+  end
+end
+let $i 0
+while < tel $i 3
+  run $s
+  let $i + tel $i 1
+end",
+    )?;
+    assert_eq!(variable(&interpreter, "calls"), Some(Argument::Integer(6)));
+    assert_eq!(variable(&interpreter, "i"), Some(Argument::Integer(3)));
+    Ok(())
+}
+
+fn log(interpreter: &mut Interpreter, code: &str) -> Result<Option<Argument>> {
+    interpreter.eval(&format!("let $log '\"\n{code}"))?;
+    assert!(interpreter.state().nesting.is_empty());
+    Ok(variable(interpreter, "log"))
+}
+
+fn logged(text: &str) -> Argument {
+    Argument::KfkString(text.into())
+}
+
+#[test]
+fn break_in_if() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let code = "let $i 0
+while true
+  let $i + tel $i 1
+  if == tel $i 3
+    break
+  end
+  let $log str::concat tel $log str::cast tel $i
+end";
+    assert_eq!(log(&mut interpreter, code)?, Some(logged("12")));
+    Ok(())
+}
+
+#[test]
+fn continue_in_if() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let code = "let $i 0
+while < tel $i 5
+  let $i + tel $i 1
+  if == % tel $i 2 0
+    continue
+  end
+  let $log str::concat tel $log str::cast tel $i
+end";
+    assert_eq!(log(&mut interpreter, code)?, Some(logged("135")));
+    Ok(())
+}
+
+#[test]
+fn break_in_else() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let code = "for $x list::new@5 1 2 3 4 5
+  if < tel $x 3
+    let $log str::concat tel $log str::cast tel $x
+  else
+    break
+  end
+  let $log str::concat tel $log ',\"
+end";
+    assert_eq!(log(&mut interpreter, code)?, Some(logged("1,2,")));
+    Ok(())
+}
+
+#[test]
+fn continue_in_else() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let code = "for $x list::new@4 1 2 3 4
+  if == % tel $x 2 1
+    let $log str::concat tel $log str::cast tel $x
+  else
+    continue
+  end
+  let $log str::concat tel $log ',\"
+end";
+    assert_eq!(log(&mut interpreter, code)?, Some(logged("1,3,")));
+    Ok(())
+}
+
+#[test]
+fn break_leaves_only_the_innermost_loop() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let code = "let $i 0
+while < tel $i 3
+  let $i + tel $i 1
+  for $j list::new@3 1 2 3
+    if == tel $j 2
+      break
+    end
+    let $log str::concat tel $log str::cast tel $j
+  end
+  let $log str::concat tel $log '|\"
+end";
+    assert_eq!(log(&mut interpreter, code)?, Some(logged("1|1|1|")));
+    Ok(())
+}
+
+#[test]
+fn continue_continues_only_the_innermost_loop() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let code = "for $x list::new@2 'a\" 'b\"
+  let $log str::concat tel $log tel $x
+  let $j 0
+  while < tel $j 3
+    let $j + tel $j 1
+    if == tel $j 2
+      continue
+    end
+    let $log str::concat tel $log str::cast tel $j
+  end
+end";
+    assert_eq!(log(&mut interpreter, code)?, Some(logged("a13b13")));
+    Ok(())
+}
+
+#[test]
+fn break_and_continue_outside_of_a_loop() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let error = interpreter.eval("break").err().map(|error| error.to_string());
+    assert_eq!(error.as_deref(), Some("Cannot use break outside of a loop in line 1"));
+    let error = interpreter
+        .eval("if true\n  continue\nend")
+        .err()
+        .map(|error| error.to_string());
+    assert_eq!(error.as_deref(), Some("Cannot use continue outside of a loop in line 2"));
+    // a subroutine can't break out of the loop it is run in
+    let error = interpreter
+        .eval("subroutine $s\n  break\nend\nwhile true\n  run $s\nend")
+        .err()
+        .map(|error| error.to_string());
+    assert_eq!(error.as_deref(), Some("Cannot use break outside of a loop in line 2"));
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+fn error(interpreter: &mut Interpreter, code: &str) -> Option<String> {
+    interpreter.eval(code).err().map(|error| error.to_string())
+}
+
+#[test]
+fn keys_are_unique() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $m map::new@6 $a 1 $b 2 $a 3")?;
+    assert_eq!(interpreter.eval("map::len tel $m")?, Argument::Integer(2));
+    assert_eq!(interpreter.eval("map::get tel $m $a")?, Argument::Integer(3));
+    assert_eq!(interpreter.eval("str::cast tel $m")?, Argument::KfkString("{'a\": 3, 'b\": 2}".into()));
+    Ok(())
+}
+
+#[test]
+fn set_overwrites_existing_keys() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $m map::new@2 $a 1")?;
+    interpreter.eval("map::set $m $a 2\nmap::set $m $b 3")?;
+    assert_eq!(interpreter.eval("map::len tel $m")?, Argument::Integer(2));
+    assert_eq!(interpreter.eval("map::get tel $m $a")?, Argument::Integer(2));
+    assert_eq!(interpreter.eval("map::has tel $m $b")?, Argument::Bool(true));
+    interpreter.eval("map::remove $m $a")?;
+    assert_eq!(interpreter.eval("map::has tel $m $a")?, Argument::Bool(false));
+    assert_eq!(interpreter.eval("map::keys tel $m")?, Argument::List(vec![Argument::KfkString("b".into())]));
+    assert_eq!(interpreter.eval("map::values tel $m")?, Argument::List(vec![Argument::Integer(3)]));
+    Ok(())
+}
+
+#[test]
+fn equality_ignores_the_order() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("== map::new@4 $a 1 $b 2 map::new@4 $b 2 $a 1")?, Argument::Bool(true));
+    assert_eq!(interpreter.eval("== map::new@4 $a 1 $b 2 map::new@4 $a 2 $b 1")?, Argument::Bool(false));
+    assert_eq!(interpreter.eval("== map::new@2 $a 1 map::new@4 $a 1 $b 2")?, Argument::Bool(false));
+    Ok(())
+}
+
+#[test]
+fn hashing_ignores_the_order() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let first = interpreter.eval("map::new@4 $a 1 $b 2")?;
+    let reordered = interpreter.eval("map::new@4 $b 2 $a 1")?;
+    let other = interpreter.eval("map::new@4 $a 2 $b 1")?;
+    let set: HashSet<Argument> = [first, reordered, other].into_iter().collect();
+    assert_eq!(set.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn nested_maps() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("let $inner map::new@2 $x 1\nlet $outer map::new@4 $inner tel $inner $list list::new@1 tel $inner")?;
+    assert_eq!(interpreter.eval("map::get map::get tel $outer $inner $x")?, Argument::Integer(1));
+    assert_eq!(
+        interpreter.eval("map::get list::get map::get tel $outer $list 0 $x")?,
+        Argument::Integer(1)
+    );
+    assert_eq!(
+        interpreter.eval("str::cast tel $outer")?,
+        Argument::KfkString("{'inner\": {'x\": 1}, 'list\": [{'x\": 1}]}".into())
+    );
+    // maps can be keys as well
+    interpreter.eval("let $keyed map::new@2 map::new@4 $a 1 $b 2 $found")?;
+    assert_eq!(interpreter.eval("map::get tel $keyed map::new@4 $b 2 $a 1")?, Argument::KfkString("found".into()));
+    Ok(())
+}
+
+#[test]
+fn errors() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(
+        error(&mut interpreter, "map::new@3 1 2 3").as_deref(),
+        Some("map::new expects keys and values in pairs but got 3 arguments in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "map::get map::new $a").as_deref(),
+        Some("No such key 'a\" found in map in line 1")
+    );
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+fn is_defined(interpreter: &Interpreter, name: &str) -> bool {
+    interpreter
+        .state()
+        .variables
+        .contains_key(&Argument::KfkString(name.into()))
+}
+
+const FIRST_ABOVE: &str = "subroutine $first-above-two
+  scope::push
+  let $i 0
+  while true
+    let $i + tel $i 1
+    if > tel $i 2
+      return tel $i
+      scope::outer::let $after-return 1
+    end
+  end
+  scope::outer::let $after-loop 1
+end";
+
+#[test]
+fn return_in_a_loop_and_an_if() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval(FIRST_ABOVE)?;
+    let scopes = interpreter.state().scopes.len();
+    assert_eq!(interpreter.eval("run $first-above-two")?, Argument::Integer(3));
+    assert_eq!(interpreter.state().scopes.len(), scopes);
+    assert!(interpreter.state().nesting.is_empty());
+    assert!(!is_defined(&interpreter, "after-return"));
+    assert!(!is_defined(&interpreter, "after-loop"));
+    assert!(!is_defined(&interpreter, "i"));
+    Ok(())
+}
+
+#[test]
+fn return_keeps_the_callers_loop_going() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval(FIRST_ABOVE)?;
+    interpreter.eval(
+        "let $sum 0
+for $x list::new@3 1 2 3
+  if true
+    let $sum + tel $sum run $first-above-two
+  end
+end",
+    )?;
+    assert_eq!(interpreter.eval("tel $sum")?, Argument::Integer(9));
+    assert!(interpreter.state().nesting.is_empty());
+    Ok(())
+}
+
+#[test]
+fn return_pops_the_scopes_of_a_keyword_body() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval(
+        "keyword $find 2
+  let $wanted tel $args@2
+  for $item tel $args@1
+    scope::push
+    if == scope::outer::tel $item scope::outer::tel $wanted
+      return true
+    end
+    scope::pop
+  end
+  return false
+end",
+    )?;
+    let scopes = interpreter.state().scopes.len();
+    assert_eq!(interpreter.eval("find list::new@3 1 2 3 2")?, Argument::Bool(true));
+    assert_eq!(interpreter.state().scopes.len(), scopes);
+    assert_eq!(interpreter.eval("find list::new@3 1 2 3 4")?, Argument::Bool(false));
+    assert_eq!(interpreter.state().scopes.len(), scopes);
+    assert!(interpreter.state().nesting.is_empty());
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+const SCRIPT: &str = "#!/usr/bin/env kfkscript
+println tel $argc
+for $arg tel $argv
+  println tel $arg
+end
+";
+
+fn write_script(name: &str, content: &str) -> std::io::Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("kfkscript-{}-{name}", std::process::id()));
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+#[test]
+fn arguments_after_the_script_are_passed_to_it() -> std::io::Result<()> {
+    let path = write_script("arguments.kfkscript", SCRIPT)?;
+    let output = Command::new(env!("CARGO_BIN_EXE_kfkscript"))
+        .arg(&path)
+        .args(["a", "-e", "--repl", "--help"])
+        .output()?;
+    std::fs::remove_file(&path)?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "4\na\n-e\n--repl\n--help\n"
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn executable_script() -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let content = SCRIPT.replacen("/usr/bin/env kfkscript", env!("CARGO_BIN_EXE_kfkscript"), 1);
+    let path = write_script("executable.kfkscript", &content)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    let output = Command::new(&path).args(["x", "--output", "y"]).output()?;
+    std::fs::remove_file(&path)?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "3\nx\n--output\ny\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn exit_code_of_executed_script() -> std::io::Result<()> {
+    let path = write_script("exit.kfkscript", "#!/usr/bin/env kfkscript\nexit 3\n")?;
+    let status = Command::new(env!("CARGO_BIN_EXE_kfkscript"))
+        .arg(&path)
+        .status()?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(status.code(), Some(3));
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+fn is_defined(interpreter: &Interpreter, name: &str) -> bool {
+    interpreter
+        .state()
+        .variables
+        .contains_key(&Argument::KfkString(name.into()))
+}
+
+#[test]
+fn or_skips_after_true() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let ret = interpreter.eval("or true let $hit 1")?;
+    assert_eq!(ret, Argument::Bool(true));
+    assert!(!is_defined(&interpreter, "hit"));
+    Ok(())
+}
+
+#[test]
+fn and_skips_after_false() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let ret = interpreter.eval("and false let $hit 1")?;
+    assert_eq!(ret, Argument::Bool(false));
+    assert!(!is_defined(&interpreter, "hit"));
+    Ok(())
+}
+
+#[test]
+fn evaluates_until_it_short_circuits() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("or false let $hit 1")?;
+    assert!(is_defined(&interpreter, "hit"));
+    Ok(())
+}
+
+// tel $missing would fail if it was evaluated
+#[test]
+fn skips_nested_keywords() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval("and false let $hit + 1 * 2 tel $missing\nlet $after 1")?;
+    assert!(!is_defined(&interpreter, "hit"));
+    assert!(is_defined(&interpreter, "after"));
+    Ok(())
+}
+
+#[test]
+fn skips_variadic_calls() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.eval(
+        "or@3 true let $hit str::concat@3 'a\" 'b\" tel $missing let $hit2 1\nlet $after 1",
+    )?;
+    assert!(!is_defined(&interpreter, "hit"));
+    assert!(!is_defined(&interpreter, "hit2"));
+    assert!(is_defined(&interpreter, "after"));
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use std::io::Cursor;
+
+use color_eyre::Result;
+use kfkscript::{Argument, Buffer, Input, Interpreter, Output};
+
+#[test]
+fn println_writes_to_the_output() -> Result<()> {
+    let buffer = Buffer::default();
+    let mut interpreter = Interpreter::new()?;
+    interpreter.set_output(Output::new(buffer.clone()));
+    interpreter.eval("println 'hello\"\nprintln + 1 2")?;
+    assert_eq!(buffer.contents(), "hello\n3\n");
+    Ok(())
+}
+
+#[test]
+fn readln_reads_from_the_input() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.set_input(Input::new(Cursor::new("first\r\nsecond\nlast")));
+    assert_eq!(interpreter.eval("readln")?, Argument::KfkString("first".into()));
+    assert_eq!(interpreter.eval("readln")?, Argument::KfkString("second".into()));
+    assert_eq!(interpreter.eval("readln")?, Argument::KfkString("last".into()));
+    assert_eq!(interpreter.eval("readln")?, Argument::Nil);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter, MixedTypes};
+
+#[test]
+fn repeat() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("str::repeat $ab 3")?, Argument::KfkString("ababab".into()));
+    assert_eq!(interpreter.eval("str::repeat $ab 0")?, Argument::KfkString(String::new()));
+    assert_eq!(interpreter.eval("str::repeat '\" 9223372036854775807")?, Argument::KfkString(String::new()));
+    Ok(())
+}
+
+#[test]
+fn repeat_too_often_is_an_error() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let error = interpreter
+        .eval("str::repeat $ab 9223372036854775807")
+        .err()
+        .map(|error| error.to_string());
+    assert_eq!(
+        error.as_deref(),
+        Some("string of length 2 repeated 9223372036854775807 times is too long in line 1")
+    );
+    Ok(())
+}
+
+#[test]
+fn plus_concatenates_strings() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("+ 'foo\" $bar")?, Argument::KfkString("foobar".into()));
+    assert_eq!(interpreter.eval("+@3 $a $b $c")?, Argument::KfkString("abc".into()));
+    Ok(())
+}
+
+#[test]
+fn plus_rejects_mixed_types_by_default() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    let error = interpreter.eval("+ $a 1").err().map(|error| error.to_string());
+    assert_eq!(
+        error.as_deref(),
+        Some("cannot use arguments of type String and Integer together with keyword + in line 1, use str::cast or --implicit-cast to concatenate them")
+    );
+    Ok(())
+}
+
+#[test]
+fn plus_casts_mixed_types_when_asked_to() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    interpreter.state_mut().mixed_types = MixedTypes::Cast;
+    assert_eq!(interpreter.eval("+ $a 1")?, Argument::KfkString("a1".into()));
+    assert_eq!(interpreter.eval("+ 1.5 $a")?, Argument::KfkString("1.5a".into()));
+    assert_eq!(interpreter.eval("+@3 1 2 $a")?, Argument::KfkString("3a".into()));
+    // only strings are concatenated, other types still can't be added
+    assert!(interpreter.eval("+ true 1").is_err());
+    Ok(())
+}
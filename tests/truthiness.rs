@@ -0,0 +1,42 @@
+use color_eyre::Result;
+use kfkscript::{Argument, Interpreter};
+
+const VALUES: [(&str, bool); 14] = [
+    ("true", true),
+    ("false", false),
+    ("nil", false),
+    ("2", true),
+    ("0", false),
+    ("-1", true),
+    ("0.5", true),
+    ("0.0", false),
+    ("'\"", false),
+    ("$a", true),
+    ("list::new", false),
+    ("list::new@1 0", true),
+    ("map::new", false),
+    ("map::new@2 0 0", true),
+];
+
+fn truthiness(interpreter: &mut Interpreter, code: &str) -> Result<Argument> {
+    interpreter.eval(&format!("let $result false\n{code}"))?;
+    interpreter.eval("tel $result")
+}
+
+#[test]
+fn if_while_not_and_or_agree() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    for (value, truthy) in VALUES {
+        let expected = Argument::Bool(truthy);
+        let by_if = truthiness(&mut interpreter, &format!("if {value}\n  let $result true\nend"))?;
+        assert_eq!(by_if, expected, "if {value}");
+        let by_else = truthiness(&mut interpreter, &format!("if {value}\nelse\n  let $result true\nend"))?;
+        assert_eq!(by_else, Argument::Bool(!truthy), "else of if {value}");
+        let by_while = truthiness(&mut interpreter, &format!("while {value}\n  let $result true\n  break\nend"))?;
+        assert_eq!(by_while, expected, "while {value}");
+        assert_eq!(interpreter.eval(&format!("! {value}"))?, Argument::Bool(!truthy), "! {value}");
+        assert_eq!(interpreter.eval(&format!("and {value} true"))?, expected, "and {value}");
+        assert_eq!(interpreter.eval(&format!("or {value} false"))?, expected, "or {value}");
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use color_eyre::Result;
+use kfkscript::{Argument, Buffer, Interpreter, Output};
+
+fn error(interpreter: &mut Interpreter, code: &str) -> Option<String> {
+    interpreter.eval(code).err().map(|error| error.to_string())
+}
+
+#[test]
+fn default_number_of_arguments() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    // without @N + takes two arguments, the third is its own expression
+    assert_eq!(interpreter.eval("+ 1 2\n* 3 4")?, Argument::Integer(12));
+    Ok(())
+}
+
+#[test]
+fn number_of_arguments_at_the_call_site() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(interpreter.eval("+@4 1 2 3 4")?, Argument::Integer(10));
+    assert_eq!(interpreter.eval("+@1 5")?, Argument::Integer(5));
+    assert_eq!(interpreter.eval("-@3 10 1 2")?, Argument::Integer(7));
+    assert_eq!(
+        interpreter.eval("list::len list::new@3 1 2 3")?,
+        Argument::Integer(3)
+    );
+    assert_eq!(interpreter.eval("list::new")?, Argument::List(vec![]));
+    Ok(())
+}
+
+#[test]
+fn variadic_println() -> Result<()> {
+    let buffer = Buffer::default();
+    let mut interpreter = Interpreter::new()?;
+    interpreter.set_output(Output::new(buffer.clone()));
+    interpreter.eval("println@3 $a 1 true\nprintln $b")?;
+    assert_eq!(buffer.contents(), "a 1 true\nb\n");
+    Ok(())
+}
+
+#[test]
+fn invalid_calls() -> Result<()> {
+    let mut interpreter = Interpreter::new()?;
+    assert_eq!(
+        error(&mut interpreter, "%@3 1 1 1").as_deref(),
+        Some("keyword % does not take a variable number of arguments in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "+@x 1").as_deref(),
+        Some("invalid number of arguments x for keyword + in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "+@0").as_deref(),
+        Some("No arguments supplied to keyword + in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "+@3 1 2").as_deref(),
+        Some("Not enough arguments supplied to keyword + in line 1")
+    );
+    assert_eq!(
+        error(&mut interpreter, "foo@2 1 2").as_deref(),
+        Some("keyword foo@2 not implemented in line 1")
+    );
+    Ok(())
+}